@@ -0,0 +1,222 @@
+use bytes::Bytes;
+use hyper::{Body, Method, Request, Response, StatusCode};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+
+use crate::api::server::{empty_response, json_response, ApiState};
+use crate::common::data::{ActionableEvent, DataEvent, DataEventMeta, ElId, TriggeredEvent};
+use crate::coordinator::{ActionReport, TriggerReport};
+use crate::health::{ComponentKind, ComponentReport};
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct InjectRequest {
+    #[serde(default)]
+    payload: String,
+    topic: Option<String>,
+}
+
+impl InjectRequest {
+    fn into_data_event(self) -> DataEvent {
+        DataEvent {
+            meta: match self.topic {
+                Some(topic) => DataEventMeta::MqttMetadata { topic },
+                None => DataEventMeta::None,
+            },
+            payload: Bytes::from(self.payload),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct InputReport {
+    id: String,
+    status: Option<ComponentReport>,
+    triggers: Vec<TriggerReport>,
+}
+
+#[derive(Debug, Serialize)]
+struct OutputReport {
+    id: String,
+    status: Option<ComponentReport>,
+    actions: Vec<ActionReport>,
+}
+
+/// Routes `/api/...` requests, `None` means the path is not an admin one
+pub async fn handle(state: &ApiState, request: Request<Body>) -> Option<Response<Body>> {
+    let method = request.method().clone();
+    let path = request.uri().path().to_string();
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+
+    let response = match (&method, segments.as_slice()) {
+        (&Method::GET, ["api", "inputs"]) => json_response(StatusCode::OK, &inputs(state)),
+        (&Method::GET, ["api", "outputs"]) => json_response(StatusCode::OK, &outputs(state)),
+        (&Method::GET, ["api", "handlers"]) => {
+            json_response(StatusCode::OK, &state.control.handlers())
+        }
+        (&Method::GET, ["api", "leader"]) => {
+            json_response(StatusCode::OK, &state.control.leadership())
+        }
+        (
+            &Method::POST,
+            ["api", "inputs", input_id, "triggers", trigger_id, switch @ ("enable" | "disable")],
+        ) => {
+            let enabled = *switch == "enable";
+            match state.control.set_trigger_enabled(
+                &ElId::from(*input_id),
+                &ElId::from(*trigger_id),
+                enabled,
+            ) {
+                true => {
+                    info!("Trigger[{}::{}] {}d via API", input_id, trigger_id, switch);
+                    empty_response(StatusCode::NO_CONTENT)
+                }
+                false => empty_response(StatusCode::NOT_FOUND),
+            }
+        }
+        (&Method::POST, ["api", "handlers", index, switch @ ("enable" | "disable")]) => {
+            let enabled = *switch == "enable";
+            match index
+                .parse::<usize>()
+                .map(|index| state.control.set_handler_enabled(index, enabled))
+            {
+                Ok(true) => {
+                    info!("Handler[{}] {}d via API", index, switch);
+                    empty_response(StatusCode::NO_CONTENT)
+                }
+                _ => empty_response(StatusCode::NOT_FOUND),
+            }
+        }
+        (&Method::POST, ["api", "inputs", input_id, "triggers", trigger_id, "inject"]) => {
+            let input_id = ElId::from(*input_id);
+            let trigger_id = ElId::from(*trigger_id);
+            if !state.control.has_trigger(&input_id, &trigger_id) {
+                return Some(empty_response(StatusCode::NOT_FOUND));
+            }
+
+            match read_inject_request(request).await {
+                Ok(inject) => {
+                    let event = TriggeredEvent {
+                        input: input_id,
+                        trigger: trigger_id,
+                        data: inject.into_data_event(),
+                    };
+                    info!("Injecting {:?} via API", event);
+                    inject_response(state.control.inject_triggered(event).await)
+                }
+                Err(response) => response,
+            }
+        }
+        (&Method::POST, ["api", "outputs", output_id, "actions", action_id, "inject"]) => {
+            let output_id = ElId::from(*output_id);
+            let action_id = ElId::from(*action_id);
+            if !state.control.has_action(&output_id, &action_id) {
+                return Some(empty_response(StatusCode::NOT_FOUND));
+            }
+            // Only the leader executes actions, the dispatcher is bypassed here
+            if !state.control.leadership().leader {
+                warn!("Not injecting into {}::{} in standby", output_id, action_id);
+                return Some(empty_response(StatusCode::CONFLICT));
+            }
+
+            match read_inject_request(request).await {
+                Ok(inject) => {
+                    let event = ActionableEvent {
+                        output: output_id,
+                        action: action_id,
+                        data: inject.into_data_event(),
+                    };
+                    info!("Injecting {:?} via API", event);
+                    inject_response(state.control.inject_actionable(event).await)
+                }
+                Err(response) => response,
+            }
+        }
+        (_, ["api", ..]) => empty_response(StatusCode::NOT_FOUND),
+        _ => return None,
+    };
+
+    Some(response)
+}
+
+fn inputs(state: &ApiState) -> Vec<InputReport> {
+    let health = state.health.report(&state.health_config);
+    let triggers = state.control.triggers();
+
+    state
+        .control
+        .input_ids()
+        .into_iter()
+        .map(|id| {
+            let id = id.to_string();
+            InputReport {
+                status: find_status(&health.components, ComponentKind::Input, &id),
+                triggers: triggers.iter().filter(|x| x.input == id).cloned().collect(),
+                id,
+            }
+        })
+        .collect()
+}
+
+fn outputs(state: &ApiState) -> Vec<OutputReport> {
+    let health = state.health.report(&state.health_config);
+    let actions = state.control.actions();
+
+    state
+        .control
+        .output_ids()
+        .into_iter()
+        .map(|id| {
+            let id = id.to_string();
+            OutputReport {
+                status: find_status(&health.components, ComponentKind::Output, &id),
+                actions: actions.iter().filter(|x| x.output == id).cloned().collect(),
+                id,
+            }
+        })
+        .collect()
+}
+
+fn find_status(
+    components: &[ComponentReport],
+    kind: ComponentKind,
+    id: &str,
+) -> Option<ComponentReport> {
+    components
+        .iter()
+        .find(|x| x.kind == kind && x.id == id)
+        .cloned()
+}
+
+async fn read_inject_request(
+    request: Request<Body>,
+) -> std::result::Result<InjectRequest, Response<Body>> {
+    let body = hyper::body::to_bytes(request.into_body())
+        .await
+        .map_err(|err| {
+            warn!("Can not read request body: {:?}", err);
+            empty_response(StatusCode::BAD_REQUEST)
+        })?;
+
+    if body.is_empty() {
+        return Ok(InjectRequest {
+            payload: String::new(),
+            topic: None,
+        });
+    }
+
+    serde_json::from_slice(&body).map_err(|err| {
+        warn!("Can not parse inject request: {:?}", err);
+        empty_response(StatusCode::BAD_REQUEST)
+    })
+}
+
+fn inject_response(result: crate::common::types::Result<()>) -> Response<Body> {
+    match result {
+        Ok(()) => empty_response(StatusCode::ACCEPTED),
+        Err(err) => {
+            warn!("Can not inject event: {:?}", err);
+            empty_response(StatusCode::SERVICE_UNAVAILABLE)
+        }
+    }
+}
@@ -0,0 +1,4 @@
+mod admin;
+mod server;
+
+pub use server::ApiServer;
@@ -0,0 +1,147 @@
+use std::convert::Infallible;
+use std::fmt::{Display, Formatter};
+
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{header, Body, Method, Request, Response, Server, StatusCode};
+use log::{error, info, trace};
+use serde::Serialize;
+
+use crate::api::admin;
+use crate::config::api::ApiConfig;
+use crate::config::health::HealthConfig;
+use crate::coordinator::{DispatcherControl, LeadershipReport};
+use crate::health::{HealthRegistry, HealthReport};
+
+#[derive(Debug, Clone)]
+pub(crate) struct ApiState {
+    pub health: HealthRegistry,
+    pub health_config: HealthConfig,
+    pub control: DispatcherControl,
+}
+
+/// Health report of `/healthz` and `/readyz`, with the leadership when there is an election
+#[derive(Debug, Serialize)]
+struct ProbeReport {
+    #[serde(flatten)]
+    health: HealthReport,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    leadership: Option<LeadershipReport>,
+}
+
+impl ProbeReport {
+    fn new(state: &ApiState) -> Self {
+        let leadership = state.control.leadership();
+        Self {
+            health: state.health.report(&state.health_config),
+            leadership: Some(leadership).filter(|x| x.election),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ApiServer {
+    config: ApiConfig,
+    state: ApiState,
+}
+
+impl ApiServer {
+    pub fn new(
+        config: ApiConfig,
+        health: HealthRegistry,
+        health_config: HealthConfig,
+        control: DispatcherControl,
+    ) -> Self {
+        Self {
+            config,
+            state: ApiState {
+                health,
+                health_config,
+                control,
+            },
+        }
+    }
+
+    pub async fn run(self) {
+        let state = self.state.clone();
+        let admin = self.config.admin;
+        let make_service = make_service_fn(move |_conn| {
+            let state = state.clone();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |request| {
+                    let state = state.clone();
+                    async move { Ok::<_, Infallible>(handle(&state, admin, request).await) }
+                }))
+            }
+        });
+
+        let server = match Server::try_bind(&self.config.listen) {
+            Ok(builder) => builder.serve(make_service),
+            Err(err) => {
+                error!("{} can not bind to {}: {:?}", self, self.config.listen, err);
+                return;
+            }
+        };
+
+        info!("{} listening on http://{}", self, self.config.listen);
+        if let Err(err) = server.await {
+            error!("{} failed: {:?}", self, err);
+        }
+    }
+}
+
+impl Display for ApiServer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ApiServer[{}]", self.config.listen)
+    }
+}
+
+async fn handle(state: &ApiState, admin: bool, request: Request<Body>) -> Response<Body> {
+    trace!("ApiServer received {} {}", request.method(), request.uri());
+
+    if admin && request.uri().path().starts_with("/api/") {
+        if let Some(response) = admin::handle(state, request).await {
+            return response;
+        }
+        return empty_response(StatusCode::NOT_FOUND);
+    }
+
+    match (request.method(), request.uri().path()) {
+        (&Method::GET, "/healthz") => {
+            let report = ProbeReport::new(state);
+            json_response(status_for(report.health.healthy), &report)
+        }
+        (&Method::GET, "/readyz") => {
+            let report = ProbeReport::new(state);
+            json_response(status_for(report.health.ready), &report)
+        }
+        _ => empty_response(StatusCode::NOT_FOUND),
+    }
+}
+
+fn status_for(ok: bool) -> StatusCode {
+    match ok {
+        true => StatusCode::OK,
+        false => StatusCode::SERVICE_UNAVAILABLE,
+    }
+}
+
+pub(crate) fn json_response<T: Serialize>(status: StatusCode, body: &T) -> Response<Body> {
+    match serde_json::to_vec(body) {
+        Ok(body) => Response::builder()
+            .status(status)
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body))
+            .unwrap(),
+        Err(err) => {
+            error!("Can not serialize response: {:?}", err);
+            empty_response(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub(crate) fn empty_response(status: StatusCode) -> Response<Body> {
+    Response::builder()
+        .status(status)
+        .body(Body::empty())
+        .unwrap()
+}
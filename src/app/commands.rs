@@ -0,0 +1,36 @@
+use std::fs;
+
+use crate::common::types::Result;
+use crate::config::format::ConfigFormat;
+use crate::config::opt::{Command, Opt};
+use crate::config::Config;
+
+pub fn execute(opt: &Opt, command: &Command) -> Result<()> {
+    match command {
+        Command::Convert { output, to } => convert(opt, output, *to),
+        Command::Schema { output } => schema(output.as_deref()),
+    }
+}
+
+fn convert(opt: &Opt, output: &str, to: Option<ConfigFormat>) -> Result<()> {
+    let config = Config::load_unresolved(&opt.config_path, opt.format)?;
+
+    match output {
+        "-" => {
+            print!("{}", config.dump_to_string(to.unwrap_or_default())?);
+            Ok(())
+        }
+        output => config.dump_to_file(output, to),
+    }
+}
+
+fn schema(output: Option<&str>) -> Result<()> {
+    let schema = Config::json_schema()?;
+
+    match output {
+        Some(output) => fs::write(output, schema)?,
+        None => println!("{}", schema),
+    }
+
+    Ok(())
+}
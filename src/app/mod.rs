@@ -1,55 +1,175 @@
-use log::trace;
+mod commands;
+
+use log::{error, info, trace};
+use std::os::unix::process::CommandExt;
 use std::time::Duration;
 use structopt::StructOpt;
 use tokio::runtime::Runtime;
+use tokio::signal::unix::{signal, SignalKind};
 
-use crate::config::opt::Opt;
+use crate::api::ApiServer;
+use crate::config::format::ConfigFormat;
+use crate::config::opt::{Command, Opt};
 use crate::config::Config;
-use crate::coordinator::ChannelManager;
+use crate::coordinator::{ChannelManager, Lifecycle, LifecycleEvent};
+use crate::health::{watchdog, HealthRegistry};
+
+/// Set on the re-executed process to emit `config_reloaded` instead of `startup`
+const RELOADED_ENV: &str = "MQRT_RELOADED";
+/// Time given to `shutdown` handlers and actions in flight to be delivered
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(2);
+
+/// Why the router stops
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Exit {
+    Terminate,
+    Reload,
+}
 
 #[derive(Debug)]
 pub struct Application {
     pub opt: Opt,
     pub config: Config,
     pub runtime: Runtime,
+    /// Started by a reload, fires `config_reloaded` instead of `startup`
+    pub reloaded: bool,
 }
 
 impl Application {
-    pub fn new() -> Self {
-        env_logger::init();
-
-        let opt: Opt = Opt::from_args();
-
-        let config = load_config(&opt.config_path);
+    pub fn new(opt: Opt) -> Self {
+        let config = load_config(&opt.config_path, opt.format);
         trace!("Loaded config from {}:\n{:#?}", &opt.config_path, config);
 
+        // Still single-threaded, the environment can be changed safely
+        let reloaded = std::env::var_os(RELOADED_ENV).is_some();
+        std::env::remove_var(RELOADED_ENV);
+
         let runtime = build_runtime();
 
         Self {
             opt,
             config,
             runtime,
+            reloaded,
         }
     }
 
     pub fn run(self) {
         let runtime = self.runtime;
-        runtime.block_on(async move {
-            ChannelManager::run(self.config.clone()).await;
-            loop {
-                // TODO: add signal handling to wait for it and gracefully exit
-                tokio::time::sleep(Duration::from_secs(1)).await;
+        let opt = self.opt;
+        let exit = runtime.block_on(async move {
+            let health = HealthRegistry::new();
+            let lifecycle = Lifecycle::new();
+
+            let control =
+                ChannelManager::run(self.config.clone(), health.clone(), lifecycle.clone()).await;
+
+            if let Some(api_config) = self.config.api.clone() {
+                let server = ApiServer::new(
+                    api_config,
+                    health.clone(),
+                    self.config.health.clone(),
+                    control,
+                );
+                tokio::spawn(async move { server.run().await });
+            }
+
+            if self.config.health.systemd_notify {
+                watchdog::notify_ready();
+                tokio::spawn(watchdog::run(health, self.config.health.clone()));
+            }
+
+            if self.reloaded {
+                lifecycle.emit(LifecycleEvent::ConfigReloaded);
+            } else {
+                lifecycle.emit(LifecycleEvent::Startup);
+            }
+
+            let mut terminate = signal(SignalKind::terminate()).expect("Can not handle SIGTERM");
+            let mut interrupt = signal(SignalKind::interrupt()).expect("Can not handle SIGINT");
+            let mut hangup = signal(SignalKind::hangup()).expect("Can not handle SIGHUP");
+
+            let exit = loop {
+                tokio::select! {
+                    _ = terminate.recv() => break Exit::Terminate,
+                    _ = interrupt.recv() => break Exit::Terminate,
+                    _ = hangup.recv() => if can_reload(&opt) {
+                        break Exit::Reload;
+                    },
+                }
+            };
+
+            match exit {
+                Exit::Terminate => {
+                    info!("Shutting down");
+                    if lifecycle.emit(LifecycleEvent::Shutdown) > 0 {
+                        tokio::time::sleep(SHUTDOWN_GRACE).await;
+                    }
+                }
+                Exit::Reload => {
+                    info!("Reloading config from {}", opt.config_path);
+                    if self.config.health.systemd_notify {
+                        watchdog::notify_reloading();
+                    }
+                    // Actions in flight are delivered by the old process, `config_reloaded`
+                    // handlers follow in the new one
+                    tokio::time::sleep(SHUTDOWN_GRACE).await;
+                }
             }
+            exit
         });
+        // Outputs are dropped here, e.g. SQLite writes its last batch
+        runtime.shutdown_timeout(Duration::from_secs(1));
+
+        if exit == Exit::Reload {
+            reload();
+        }
     }
 }
 
 impl Default for Application {
     fn default() -> Self {
-        Self::new()
+        Self::new(Opt::from_args())
+    }
+}
+
+/// Runs a one-off command instead of the router, exits with non-zero code on failure
+pub fn execute_command(opt: &Opt, command: &Command) {
+    if let Err(err) = commands::execute(opt, command) {
+        error!("Command failed: {}", err);
+        eprintln!("{}", err);
+        std::process::exit(1);
     }
 }
 
+/// Validates the new config, the running one is kept if it is invalid
+fn can_reload(opt: &Opt) -> bool {
+    match Config::load(&opt.config_path, opt.format) {
+        Ok(_) => true,
+        Err(err) => {
+            error!(
+                "Not reloading, can not load config from {}: {}",
+                opt.config_path, err
+            );
+            false
+        }
+    }
+}
+
+/// Re-executes the process with the same arguments, the new one loads the config again
+fn reload() {
+    let err = match std::env::current_exe() {
+        Ok(exe) => std::process::Command::new(exe)
+            .args(std::env::args_os().skip(1))
+            .env(RELOADED_ENV, "1")
+            .exec(),
+        Err(err) => err,
+    };
+    // The router is already stopped, there is nothing to keep running
+    error!("Can not reload: {}", err);
+    std::process::exit(1);
+}
+
 fn build_runtime() -> Runtime {
     tokio::runtime::Builder::new_multi_thread()
         .enable_all()
@@ -58,6 +178,7 @@ fn build_runtime() -> Runtime {
         .expect("Can not spawn runtime workers")
 }
 
-fn load_config(config_path: &str) -> Config {
-    Config::load(config_path).unwrap_or_else(|_| panic!("Can not load config from {}", config_path))
+fn load_config(config_path: &str, format: Option<ConfigFormat>) -> Config {
+    Config::load(config_path, format)
+        .unwrap_or_else(|err| panic!("Can not load config from {}: {}", config_path, err))
 }
@@ -0,0 +1,55 @@
+use lapin::uri::AMQPUri;
+use lapin::{Connection, ConnectionProperties};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::common::secret::{self, Secret};
+use crate::common::types::Result;
+
+/// Connection settings shared by AMQP inputs and outputs
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct AmqpConnectionConfig {
+    /// E.g. `amqp://localhost:5672/%2f`, keep the password out of it and use `password` instead
+    url: String,
+    username: Option<String>,
+    password: Option<Secret>,
+    password_file: Option<String>,
+}
+
+impl AmqpConnectionConfig {
+    pub fn resolve_secrets(&mut self) -> Result<()> {
+        secret::resolve(&mut self.password, &mut self.password_file)
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        self.uri().map(|_| ())
+    }
+
+    fn uri(&self) -> Result<AMQPUri> {
+        let mut uri: AMQPUri = self
+            .url
+            .parse()
+            .map_err(|err| format!("Invalid url {:?}: {}", self.url, err))?;
+        if let Some(username) = &self.username {
+            uri.authority.userinfo.username = username.clone();
+        }
+        if let Some(password) = &self.password {
+            uri.authority.userinfo.password = password.expose().to_string();
+        }
+
+        Ok(uri)
+    }
+
+    /// `name` is shown in the connection list of the broker
+    pub async fn connect(&self, name: String) -> Result<Connection> {
+        let properties = ConnectionProperties::default().with_connection_name(name.into());
+
+        Ok(Connection::connect_uri(self.uri()?, properties).await?)
+    }
+
+    pub fn host(&self) -> String {
+        self.uri()
+            .map(|uri| format!("{}:{}", uri.authority.host, uri.authority.port))
+            .unwrap_or_default()
+    }
+}
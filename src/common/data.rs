@@ -1,4 +1,7 @@
 use bytes::Bytes;
+use schemars::gen::SchemaGenerator;
+use schemars::schema::Schema;
+use schemars::JsonSchema;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 use std::fmt::{Display, Error, Formatter};
@@ -6,14 +9,14 @@ use std::fmt::{Display, Error, Formatter};
 use std::str::FromStr;
 
 // ElId
-#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[derive(Debug, Clone, Eq, PartialEq, Hash, PartialOrd, Ord)]
 pub struct ElId {
     pub id: String,
 }
 
 impl Serialize for ElId {
     fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
-        [&self.id].serialize(serializer)
+        self.id.serialize(serializer)
     }
 }
 
@@ -24,12 +27,32 @@ impl<'de> Deserialize<'de> for ElId {
     }
 }
 
+impl JsonSchema for ElId {
+    fn is_referenceable() -> bool {
+        false
+    }
+
+    fn schema_name() -> String {
+        "ElId".to_string()
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        String::json_schema(gen)
+    }
+}
+
 impl Display for ElId {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.id)
     }
 }
 
+impl From<&str> for ElId {
+    fn from(s: &str) -> Self {
+        ElId { id: s.to_string() }
+    }
+}
+
 impl FromStr for ElId {
     type Err = Error;
 
@@ -44,6 +67,7 @@ pub type InputId = ElId;
 pub type TriggerId = ElId;
 pub type ActionId = ElId;
 pub type OutputId = ElId;
+pub type ConnectionId = ElId;
 
 #[derive(Debug, Clone)]
 pub struct DataEvent {
@@ -51,10 +75,156 @@ pub struct DataEvent {
     pub payload: Bytes,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
 pub enum DataEventMeta {
     None,
-    MqttMetadata { topic: String },
+    #[serde(rename = "mqtt")]
+    MqttMetadata {
+        topic: String,
+    },
+    Http {
+        method: String,
+        path: String,
+        query: Vec<(String, String)>,
+        headers: Vec<(String, String)>,
+    },
+    Schedule {
+        scheduled_at: String,
+        timestamp: i64,
+    },
+    File {
+        path: String,
+    },
+    Socket {
+        peer: String,
+    },
+    WebSocket {
+        channel: String,
+        peer: String,
+    },
+    Redis {
+        channel: String,
+        /// Stream entry id, not set for pub/sub messages
+        id: Option<String>,
+    },
+    Amqp {
+        exchange: String,
+        routing_key: String,
+    },
+    Kafka {
+        topic: String,
+        key: Option<String>,
+        partition: i32,
+        offset: i64,
+        headers: Vec<(String, String)>,
+    },
+    Internal {
+        channel: String,
+        depth: u32,
+    },
+    Lifecycle {
+        event: String,
+        /// Input of `input_connected` and `input_disconnected` events
+        input: Option<String>,
+    },
+}
+
+impl DataEventMeta {
+    /// Metadata field by name, e.g. `topic` for MQTT or `query.<name>` for HTTP
+    pub fn get(&self, name: &str) -> Option<String> {
+        match self {
+            DataEventMeta::None => None,
+            DataEventMeta::MqttMetadata { topic } => match name {
+                "topic" => Some(topic.clone()),
+                _ => None,
+            },
+            DataEventMeta::Http {
+                method,
+                path,
+                query,
+                headers,
+            } => match name {
+                "method" => Some(method.clone()),
+                "path" => Some(path.clone()),
+                _ => {
+                    if let Some(key) = name.strip_prefix("query.") {
+                        find_pair(query, key)
+                    } else if let Some(key) = name.strip_prefix("headers.") {
+                        find_pair(headers, &key.to_lowercase())
+                    } else {
+                        None
+                    }
+                }
+            },
+            DataEventMeta::Schedule {
+                scheduled_at,
+                timestamp,
+            } => match name {
+                "scheduled_at" => Some(scheduled_at.clone()),
+                "timestamp" => Some(timestamp.to_string()),
+                _ => None,
+            },
+            DataEventMeta::File { path } => match name {
+                "path" => Some(path.clone()),
+                _ => None,
+            },
+            DataEventMeta::Socket { peer } => match name {
+                "peer" => Some(peer.clone()),
+                _ => None,
+            },
+            DataEventMeta::WebSocket { channel, peer } => match name {
+                "channel" => Some(channel.clone()),
+                "peer" => Some(peer.clone()),
+                _ => None,
+            },
+            DataEventMeta::Redis { channel, id } => match name {
+                "channel" => Some(channel.clone()),
+                "id" => id.clone(),
+                _ => None,
+            },
+            DataEventMeta::Amqp {
+                exchange,
+                routing_key,
+            } => match name {
+                "exchange" => Some(exchange.clone()),
+                "routing_key" => Some(routing_key.clone()),
+                _ => None,
+            },
+            DataEventMeta::Kafka {
+                topic,
+                key,
+                partition,
+                offset,
+                headers,
+            } => match name {
+                "topic" => Some(topic.clone()),
+                "key" => key.clone(),
+                "partition" => Some(partition.to_string()),
+                "offset" => Some(offset.to_string()),
+                _ => name
+                    .strip_prefix("headers.")
+                    .and_then(|header| find_pair(headers, header)),
+            },
+            DataEventMeta::Internal { channel, depth } => match name {
+                "channel" => Some(channel.clone()),
+                "depth" => Some(depth.to_string()),
+                _ => None,
+            },
+            DataEventMeta::Lifecycle { event, input } => match name {
+                "event" => Some(event.clone()),
+                "input" => input.clone(),
+                _ => None,
+            },
+        }
+    }
+}
+
+fn find_pair(pairs: &[(String, String)], key: &str) -> Option<String> {
+    pairs
+        .iter()
+        .find(|(name, _)| name == key)
+        .map(|(_, value)| value.clone())
 }
 
 impl Default for DataEventMeta {
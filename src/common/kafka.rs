@@ -0,0 +1,48 @@
+use std::collections::HashMap;
+
+use rdkafka::ClientConfig;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::common::secret::{self, Secret};
+use crate::common::types::Result;
+
+/// Connection settings shared by Kafka inputs and outputs
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct KafkaConnectionConfig {
+    /// Comma separated `host:port` list
+    brokers: String,
+    /// SASL credentials, `security.protocol` and `sasl.mechanism` go to `properties`
+    username: Option<String>,
+    password: Option<Secret>,
+    password_file: Option<String>,
+    /// Any other librdkafka property, e.g. `"compression.type" = "lz4"`
+    #[serde(default)]
+    properties: HashMap<String, String>,
+}
+
+impl KafkaConnectionConfig {
+    pub fn resolve_secrets(&mut self) -> Result<()> {
+        secret::resolve(&mut self.password, &mut self.password_file)
+    }
+
+    pub fn client_config(&self) -> ClientConfig {
+        let mut config = ClientConfig::new();
+        config.set("bootstrap.servers", &self.brokers);
+        if let Some(username) = &self.username {
+            config.set("sasl.username", username);
+        }
+        if let Some(password) = &self.password {
+            config.set("sasl.password", password.expose());
+        }
+        for (key, value) in &self.properties {
+            config.set(key, value);
+        }
+
+        config
+    }
+
+    pub fn brokers(&self) -> &str {
+        &self.brokers
+    }
+}
@@ -1,3 +1,11 @@
+pub mod amqp;
 pub mod data;
+#[cfg(feature = "kafka")]
+pub mod kafka;
+pub mod mqtt;
+pub mod redis;
+pub mod secret;
+pub mod template;
 pub mod types;
 pub mod utils;
+pub mod websocket;
@@ -0,0 +1,591 @@
+use std::collections::HashSet;
+use std::fmt::{Debug, Display, Formatter};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use futures::channel::mpsc::Receiver as StreamReceiver;
+use log::{error, trace, warn};
+use paho_mqtt::{AsyncClient, ConnectOptions, Message};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{mpsc, watch};
+use tokio_stream::StreamExt;
+
+use crate::common::secret::{self, Secret};
+use crate::common::types::Result;
+use crate::common::utils::random_alphanumeric;
+use crate::health::ComponentHealth;
+
+/// Connection settings of an MQTT input, output or a shared `[connection.<id>]`
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct MqttConnectionConfig {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    host: String,
+    #[serde(default = "default_port")]
+    port: u16,
+    #[serde(default)]
+    mqtt_version: MqttVersion,
+    /// The broker drops the connection (and publishes the Last Will) after 1.5 times of it,
+    /// 30 seconds by default
+    keep_alive_secs: Option<u64>,
+    username: Option<String>,
+    password: Option<Secret>,
+    password_file: Option<String>,
+    /// Published by the broker when the connection is lost
+    last_will: Option<MqttStatusMessageConfig>,
+    /// Published on every (re)connect
+    birth: Option<MqttStatusMessageConfig>,
+}
+
+fn default_port() -> u16 {
+    1883
+}
+
+const DEFAULT_KEEP_ALIVE_SECS: u64 = 30;
+
+impl Default for MqttConnectionConfig {
+    fn default() -> Self {
+        Self {
+            host: String::new(),
+            port: default_port(),
+            mqtt_version: MqttVersion::default(),
+            keep_alive_secs: None,
+            username: None,
+            password: None,
+            password_file: None,
+            last_will: None,
+            birth: None,
+        }
+    }
+}
+
+impl MqttConnectionConfig {
+    pub fn resolve_secrets(&mut self) -> Result<()> {
+        secret::resolve(&mut self.password, &mut self.password_file)
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if self.host.is_empty() {
+            return Err("`host` must be set".into());
+        }
+        if let Some(last_will) = &self.last_will {
+            last_will
+                .validate()
+                .map_err(|err| format!("Last Will: {}", err))?;
+        }
+        if let Some(birth) = &self.birth {
+            birth
+                .validate()
+                .map_err(|err| format!("Birth message: {}", err))?;
+        }
+
+        Ok(())
+    }
+
+    /// True if nothing is set, e.g. when a shared connection is used instead
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// True if a Last Will or a birth message is set
+    pub fn has_status_messages(&self) -> bool {
+        self.last_will.is_some() || self.birth.is_some()
+    }
+
+    /// Same settings with the status messages replaced by the Last Will only
+    pub fn with_last_will(&self, last_will: MqttStatusMessageConfig) -> Self {
+        Self {
+            last_will: Some(last_will),
+            birth: None,
+            ..self.clone()
+        }
+    }
+
+    pub fn keep_alive_secs(&self) -> Option<u64> {
+        self.keep_alive_secs
+    }
+
+    pub fn with_keep_alive(&self, keep_alive_secs: Option<u64>) -> Self {
+        Self {
+            keep_alive_secs,
+            ..self.clone()
+        }
+    }
+
+    fn server_uri(&self) -> String {
+        format!("tcp://{}:{}", self.host, self.port)
+    }
+
+    fn connect_options(&self) -> ConnectOptions {
+        let mut connect_opts = paho_mqtt::ConnectOptionsBuilder::new();
+        connect_opts
+            .keep_alive_interval(Duration::from_secs(
+                self.keep_alive_secs.unwrap_or(DEFAULT_KEEP_ALIVE_SECS),
+            ))
+            .automatic_reconnect(Duration::from_secs(1), Duration::from_secs(16))
+            .mqtt_version(self.mqtt_version.code());
+        match self.mqtt_version {
+            MqttVersion::V311 => connect_opts.clean_session(true),
+            MqttVersion::V5 => connect_opts.clean_start(true),
+        };
+
+        if let Some(username) = &self.username {
+            connect_opts.user_name(username);
+        }
+
+        if let Some(password) = &self.password {
+            connect_opts.password(password.expose());
+        }
+
+        if let Some(last_will) = &self.last_will {
+            connect_opts.will_message(last_will.message());
+        }
+
+        connect_opts.finalize()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub enum MqttVersion {
+    #[serde(rename = "3.1.1")]
+    V311,
+    #[serde(rename = "5")]
+    V5,
+}
+
+impl Default for MqttVersion {
+    fn default() -> Self {
+        MqttVersion::V311
+    }
+}
+
+impl MqttVersion {
+    fn code(&self) -> u32 {
+        match self {
+            MqttVersion::V311 => paho_mqtt::MQTT_VERSION_3_1_1,
+            MqttVersion::V5 => paho_mqtt::MQTT_VERSION_5,
+        }
+    }
+}
+
+/// Topic filter of a subscription without the shared subscription prefix, messages are
+/// received on it: `$share/<group>/<topic>` (MQTT 5, and MQTT 3.1.1 on most brokers),
+/// `$share:<group>:<topic>` (HiveMQ 3) or `$queue/<topic>` (EMQX)
+pub fn topic_filter(subscription: &str) -> &str {
+    if let Some(rest) = subscription.strip_prefix("$share/") {
+        rest.split_once('/')
+            .map_or(subscription, |(_, topic)| topic)
+    } else if let Some(rest) = subscription.strip_prefix("$share:") {
+        rest.split_once(':')
+            .map_or(subscription, |(_, topic)| topic)
+    } else if let Some(topic) = subscription.strip_prefix("$queue/") {
+        topic
+    } else {
+        subscription
+    }
+}
+
+/// Whether a message topic matches a topic filter with `+` and `#` wildcards. Filters starting
+/// with a wildcard do not match topics starting with `$`, e.g. `$SYS/...`
+pub fn topic_matches(filter: &str, topic: &str) -> bool {
+    if topic.starts_with('$') && filter.starts_with(&['+', '#'][..]) {
+        return false;
+    }
+
+    let mut topic_levels = topic.split('/');
+    for level in filter.split('/') {
+        match (level, topic_levels.next()) {
+            // Also matches the parent level, `a/#` matches `a`
+            ("#", _) => return true,
+            ("+", Some(_)) => {}
+            (level, Some(topic_level)) if level == topic_level => {}
+            _ => return false,
+        }
+    }
+
+    topic_levels.next().is_none()
+}
+
+pub fn validate_subscription(subscription: &str) -> Result<()> {
+    let group = if let Some(rest) = subscription.strip_prefix("$share/") {
+        rest.split_once('/').map(|(group, _)| group)
+    } else if let Some(rest) = subscription.strip_prefix("$share:") {
+        rest.split_once(':').map(|(group, _)| group)
+    } else {
+        Some("-")
+    };
+
+    match group {
+        None => Err(format!("Shared subscription {:?} has no topic", subscription).into()),
+        Some(group) if group.is_empty() || group.contains(&['+', '#'][..]) => {
+            Err(format!("Invalid share group in {:?}", subscription).into())
+        }
+        Some(_) if topic_filter(subscription).is_empty() => {
+            Err(format!("Empty topic in {:?}", subscription).into())
+        }
+        Some(_) => validate_topic_filter(topic_filter(subscription))
+            .map_err(|err| format!("Invalid topic {:?}: {}", subscription, err).into()),
+    }
+}
+
+fn validate_topic_filter(filter: &str) -> std::result::Result<(), &'static str> {
+    let levels = filter.split('/').collect::<Vec<_>>();
+    for (index, level) in levels.iter().enumerate() {
+        if level.contains('#') && (*level != "#" || index + 1 != levels.len()) {
+            return Err("`#` must be the whole last level");
+        }
+        if level.contains('+') && *level != "+" {
+            return Err("`+` must be a whole level");
+        }
+    }
+
+    Ok(())
+}
+
+/// Message an MQTT client publishes about itself, a Last Will or a birth message
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct MqttStatusMessageConfig {
+    topic: String,
+    payload: String,
+    #[serde(default = "default_qos")]
+    qos: i32,
+    #[serde(default = "default_retain")]
+    retain: bool,
+}
+
+fn default_qos() -> i32 {
+    paho_mqtt::QOS_1
+}
+
+fn default_retain() -> bool {
+    true
+}
+
+impl MqttStatusMessageConfig {
+    pub fn new(topic: String, payload: String, qos: i32, retain: bool) -> Self {
+        Self {
+            topic,
+            payload,
+            qos,
+            retain,
+        }
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if self.topic.is_empty() {
+            return Err("`topic` can not be empty".into());
+        }
+        if !(paho_mqtt::QOS_0..=paho_mqtt::QOS_2).contains(&self.qos) {
+            return Err(format!("`qos` must be 0, 1 or 2, got {}", self.qos).into());
+        }
+
+        Ok(())
+    }
+
+    pub fn message(&self) -> Message {
+        if self.retain {
+            Message::new_retained(self.topic.as_str(), self.payload.as_str(), self.qos)
+        } else {
+            Message::new(self.topic.as_str(), self.payload.as_str(), self.qos)
+        }
+    }
+}
+
+// Connection
+struct Subscriber {
+    /// As subscribed, possibly shared
+    subscriptions: Vec<String>,
+    /// Topic filters messages are received on
+    filters: Vec<String>,
+    chan: mpsc::Sender<Message>,
+}
+
+/// Inputs and outputs using the connection, (re)subscribed and updated on every (re)connect
+#[derive(Default)]
+struct Users {
+    subscribers: Vec<Subscriber>,
+    components: Vec<ComponentHealth>,
+}
+
+impl Users {
+    fn topics(&self) -> Vec<String> {
+        self.subscribers
+            .iter()
+            .flat_map(|x| x.subscriptions.iter().cloned())
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect()
+    }
+
+    fn set_connected(&self, connected: bool) {
+        for component in &self.components {
+            component.set_connected(connected);
+        }
+    }
+}
+
+struct Inner {
+    name: String,
+    config: MqttConnectionConfig,
+    client: AsyncClient,
+    users: Arc<Mutex<Users>>,
+    stream: Mutex<Option<StreamReceiver<Option<Message>>>>,
+    started: AtomicBool,
+    connected: watch::Receiver<bool>,
+}
+
+/// MQTT client shared by every input and output using it, messages are routed to inputs by topic
+#[derive(Clone)]
+pub struct MqttConnection {
+    inner: Arc<Inner>,
+}
+
+impl MqttConnection {
+    /// `name` identifies the client in logs and its client id, e.g. `input-<id>` or `connection-<id>`
+    pub fn new(name: String, config: MqttConnectionConfig) -> Self {
+        let create_opts = paho_mqtt::CreateOptionsBuilder::new()
+            .server_uri(config.server_uri())
+            .mqtt_version(config.mqtt_version.code())
+            .client_id(format!("mqrt-{}-{}", name, random_alphanumeric()))
+            .finalize();
+
+        let mut client = AsyncClient::new(create_opts).unwrap_or_else(|e| {
+            error!("Error creating the client: {:?}", e);
+            panic!("Can not create MQTT client")
+        });
+
+        let stream = client.get_stream(25);
+        let users = Arc::new(Mutex::new(Users::default()));
+        let (connected_tx, connected) = watch::channel(false);
+        let connected_tx = Arc::new(connected_tx);
+
+        {
+            // (re)subscribe on every (re)connect, as the session is not persisted
+            let users = users.clone();
+            let birth = config.birth.clone();
+            let connected_tx = connected_tx.clone();
+            client.set_connected_callback(move |cli| {
+                let users = users.lock().unwrap();
+                let topics = users.topics();
+                if !topics.is_empty() {
+                    trace!("Subscribing to topics: {:?}", topics);
+                    let qos: Vec<i32> = [paho_mqtt::QOS_1].repeat(topics.len());
+                    cli.subscribe_many(&topics, &qos);
+                }
+                if let Some(birth) = &birth {
+                    cli.publish(birth.message());
+                }
+                users.set_connected(true);
+                let _ = connected_tx.send(true);
+            });
+        }
+        {
+            let users = users.clone();
+            let name = format!("MqttConnection[{}]", name);
+            client.set_connection_lost_callback(move |_cli| {
+                warn!("{} lost MQTT connection, reconnecting...", name);
+                users.lock().unwrap().set_connected(false);
+                let _ = connected_tx.send(false);
+            });
+        }
+
+        Self {
+            inner: Arc::new(Inner {
+                name,
+                config,
+                client,
+                users,
+                stream: Mutex::new(Some(stream)),
+                started: AtomicBool::new(false),
+                connected,
+            }),
+        }
+    }
+
+    /// Subscribes to the topics (shared subscriptions too), messages of them are sent to the
+    /// returned channel
+    pub fn subscribe(
+        &self,
+        subscriptions: Vec<String>,
+        health: ComponentHealth,
+    ) -> mpsc::Receiver<Message> {
+        let (tx, rx) = mpsc::channel(25);
+        let qos: Vec<i32> = [paho_mqtt::QOS_1].repeat(subscriptions.len());
+
+        let mut users = self.inner.users.lock().unwrap();
+        if self.inner.client.is_connected() {
+            trace!("{} subscribing to topics: {:?}", self, subscriptions);
+            self.inner.client.subscribe_many(&subscriptions, &qos);
+        }
+        health.set_connected(self.inner.client.is_connected());
+        users.components.push(health);
+        users.subscribers.push(Subscriber {
+            filters: subscriptions
+                .iter()
+                .map(|x| topic_filter(x).to_string())
+                .collect(),
+            subscriptions,
+            chan: tx,
+        });
+
+        rx
+    }
+
+    /// Reports connection changes of the client to the component, e.g. of an output
+    pub fn register(&self, health: ComponentHealth) {
+        let mut users = self.inner.users.lock().unwrap();
+        health.set_connected(self.inner.client.is_connected());
+        users.components.push(health);
+    }
+
+    /// Connects and starts routing messages, only the first call has effect
+    pub fn start(&self) {
+        if self.inner.started.swap(true, Ordering::SeqCst) {
+            return;
+        }
+
+        let stream = self.inner.stream.lock().unwrap().take();
+        let connection = self.clone();
+        tokio::spawn(async move {
+            trace!("{} connecting to the MQTT server...", connection);
+            connection
+                .inner
+                .client
+                .connect(connection.inner.config.connect_options())
+                .await
+                .expect("Can not connect to MQTT");
+
+            if let Some(stream) = stream {
+                connection.route(stream).await;
+            }
+        });
+    }
+
+    /// Current connection state and its changes
+    pub fn connected(&self) -> watch::Receiver<bool> {
+        self.inner.connected.clone()
+    }
+
+    /// Waits until connected
+    pub async fn wait_connected(&self) {
+        let mut connected = self.inner.connected.clone();
+        while !*connected.borrow() {
+            if connected.changed().await.is_err() {
+                return;
+            }
+        }
+    }
+
+    pub async fn publish(&self, message: Message) -> Result<()> {
+        self.inner.client.publish(message).await?;
+        Ok(())
+    }
+
+    async fn route(&self, mut stream: StreamReceiver<Option<Message>>) {
+        trace!("{} waiting for messages...", self);
+
+        while let Some(some_mqtt_message) = stream.next().await {
+            let mqtt_message = match some_mqtt_message {
+                Some(mqtt_message) => mqtt_message,
+                // A "None" means we were disconnected, client will reconnect automatically
+                None => continue,
+            };
+            trace!("{} received {}", self, mqtt_message);
+
+            let chans = self
+                .inner
+                .users
+                .lock()
+                .unwrap()
+                .subscribers
+                .iter()
+                .filter(|x| {
+                    x.filters
+                        .iter()
+                        .any(|filter| topic_matches(filter, mqtt_message.topic()))
+                })
+                .map(|x| x.chan.clone())
+                .collect::<Vec<_>>();
+
+            for chan in chans {
+                chan.send(mqtt_message.clone())
+                    .await
+                    .unwrap_or_else(|err| warn!("Can not route MQTT message {:?}", &err));
+            }
+        }
+    }
+}
+
+impl Display for MqttConnection {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "MqttConnection[{}]", self.inner.name)
+    }
+}
+
+impl Debug for MqttConnection {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_exact_topics() {
+        assert!(topic_matches("home/kitchen/temp", "home/kitchen/temp"));
+        assert!(!topic_matches("home/kitchen/temp", "home/kitchen"));
+        assert!(!topic_matches("home/kitchen", "home/kitchen/temp"));
+        assert!(!topic_matches("home/kitchen/temp", "home/kitchen/temp/"));
+    }
+
+    #[test]
+    fn matches_single_level_wildcards() {
+        assert!(topic_matches("home/+/temp", "home/kitchen/temp"));
+        assert!(topic_matches("home/+/temp", "home//temp"));
+        assert!(topic_matches("+/+", "home/kitchen"));
+        assert!(!topic_matches("home/+/temp", "home/kitchen/sink/temp"));
+        assert!(!topic_matches("home/+", "home/kitchen/temp"));
+        assert!(!topic_matches("home/+", "home"));
+    }
+
+    #[test]
+    fn matches_multi_level_wildcards() {
+        assert!(topic_matches("#", "home/kitchen/temp"));
+        assert!(topic_matches("home/#", "home/kitchen/temp"));
+        assert!(topic_matches("home/#", "home"));
+        assert!(topic_matches("home/+/#", "home/kitchen"));
+        assert!(!topic_matches("home/#", "office/kitchen"));
+    }
+
+    #[test]
+    fn wildcards_skip_system_topics() {
+        assert!(!topic_matches("#", "$SYS/broker/uptime"));
+        assert!(!topic_matches("+/broker/uptime", "$SYS/broker/uptime"));
+        assert!(topic_matches("$SYS/#", "$SYS/broker/uptime"));
+    }
+
+    #[test]
+    fn matches_shared_subscriptions_by_their_filter() {
+        assert!(topic_matches(
+            topic_filter("$share/group/home/+/temp"),
+            "home/kitchen/temp"
+        ));
+        assert!(topic_matches(topic_filter("$queue/home/#"), "home/kitchen"));
+    }
+
+    #[test]
+    fn validates_wildcards() {
+        assert!(validate_subscription("home/+/temp").is_ok());
+        assert!(validate_subscription("home/#").is_ok());
+        assert!(validate_subscription("$share/group/#").is_ok());
+
+        assert!(validate_subscription("home/#/temp").is_err());
+        assert!(validate_subscription("home/kitchen#").is_err());
+        assert!(validate_subscription("home/kitchen+/temp").is_err());
+        assert!(validate_subscription("$share/gr+oup/home").is_err());
+    }
+}
@@ -0,0 +1,38 @@
+use redis::{Client, IntoConnectionInfo};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::common::secret::{self, Secret};
+use crate::common::types::Result;
+
+/// Connection settings shared by Redis inputs and outputs
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct RedisConnectionConfig {
+    /// E.g. `redis://localhost:6379/0`, keep the password out of it and use `password` instead
+    url: String,
+    username: Option<String>,
+    password: Option<Secret>,
+    password_file: Option<String>,
+}
+
+impl RedisConnectionConfig {
+    pub fn resolve_secrets(&mut self) -> Result<()> {
+        secret::resolve(&mut self.password, &mut self.password_file)
+    }
+
+    pub fn client(&self) -> Result<Client> {
+        let mut info = self.url.as_str().into_connection_info()?;
+        if let Some(username) = &self.username {
+            info.redis.username = Some(username.clone());
+        }
+        if let Some(password) = &self.password {
+            info.redis.password = Some(password.expose().to_string());
+        }
+
+        Ok(Client::open(info)?)
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+}
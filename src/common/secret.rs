@@ -0,0 +1,49 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Debug, Formatter};
+use std::fs;
+
+use crate::common::types::Result;
+
+/// String that never leaks its value through `Debug`, e.g. in the trace-level config dump
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(transparent)]
+pub struct Secret(String);
+
+impl Secret {
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<String> for Secret {
+    fn from(value: String) -> Self {
+        Secret(value)
+    }
+}
+
+impl Debug for Secret {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "\"***\"")
+    }
+}
+
+/// Reads a secret from a file (e.g. `/run/secrets/mqtt`), ignoring the trailing newline
+pub fn read_secret_file(path: &str) -> Result<Secret> {
+    let data = fs::read_to_string(path)
+        .map_err(|err| format!("Can not read secret file {}: {}", path, err))?;
+
+    Ok(Secret(data.trim_end_matches(&['\r', '\n'][..]).to_string()))
+}
+
+/// Replaces a `password_file` with the password read from it, only one of them can be set
+pub fn resolve(secret: &mut Option<Secret>, file: &mut Option<String>) -> Result<()> {
+    if let Some(path) = file.take() {
+        if secret.is_some() {
+            return Err("Only one of `password` and `password_file` can be set".into());
+        }
+        *secret = Some(read_secret_file(&path)?);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,59 @@
+use serde_json::Value;
+
+use crate::common::data::DataEvent;
+
+/// Renders `{{ name }}` placeholders from the event:
+/// - `payload` - payload as a string
+/// - `payload.<path>` - field of a JSON payload, e.g. `payload.state` or `payload.items.0.id`
+/// - anything else is looked up in the event metadata, e.g. `topic` or `query.id`
+///
+/// Unknown placeholders are rendered as empty strings.
+pub fn render(template: &str, data: &DataEvent) -> String {
+    let mut result = String::with_capacity(template.len());
+    let mut rest = template;
+    let mut payload_json: Option<Option<Value>> = None;
+
+    while let Some(start) = rest.find("{{") {
+        let end = match rest[start..].find("}}") {
+            Some(end) => start + end,
+            None => break,
+        };
+
+        result.push_str(&rest[..start]);
+        let name = rest[start + 2..end].trim();
+
+        let value = if name == "payload" {
+            Some(String::from_utf8_lossy(&data.payload).to_string())
+        } else if let Some(path) = name.strip_prefix("payload.") {
+            payload_json
+                .get_or_insert_with(|| serde_json::from_slice(&data.payload).ok())
+                .as_ref()
+                .and_then(|json| json_path(json, path))
+        } else {
+            data.meta.get(name)
+        };
+        result.push_str(&value.unwrap_or_default());
+
+        rest = &rest[end + 2..];
+    }
+    result.push_str(rest);
+
+    result
+}
+
+fn json_path(json: &Value, path: &str) -> Option<String> {
+    match json_value(json, path)? {
+        Value::String(value) => Some(value.clone()),
+        Value::Null => None,
+        value => Some(value.to_string()),
+    }
+}
+
+/// Field of a JSON value by a dot separated path, e.g. `items.0.id`
+pub fn json_value<'a>(json: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.').try_fold(json, |value, key| match value {
+        Value::Object(map) => map.get(key),
+        Value::Array(values) => key.parse::<usize>().ok().and_then(|i| values.get(i)),
+        _ => None,
+    })
+}
@@ -8,3 +8,11 @@ pub fn random_alphanumeric() -> String {
         .map(char::from)
         .collect()
 }
+
+/// Host name of the machine, if it is known
+pub fn hostname() -> Option<String> {
+    std::fs::read_to_string("/proc/sys/kernel/hostname")
+        .map(|x| x.trim().to_string())
+        .ok()
+        .filter(|x| !x.is_empty())
+}
@@ -0,0 +1,101 @@
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::net::TcpStream;
+use tokio_tungstenite::tungstenite::handshake::server::{
+    Callback, ErrorResponse, Request, Response,
+};
+use tokio_tungstenite::tungstenite::http::{header, StatusCode};
+use tokio_tungstenite::WebSocketStream;
+
+use crate::common::secret::Secret;
+use crate::common::types::Result;
+
+/// Message exchanged with WebSocket clients
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ChannelMessage {
+    pub channel: String,
+    #[serde(default)]
+    pub payload: Value,
+}
+
+impl ChannelMessage {
+    /// JSON payloads are embedded as is, anything else as a string
+    pub fn new(channel: &str, payload: &[u8]) -> Self {
+        let payload = serde_json::from_slice(payload)
+            .unwrap_or_else(|_| Value::String(String::from_utf8_lossy(payload).to_string()));
+        Self {
+            channel: channel.to_string(),
+            payload,
+        }
+    }
+
+    /// String payloads are returned as is, anything else as JSON
+    pub fn payload_bytes(&self) -> Bytes {
+        match &self.payload {
+            Value::String(payload) => Bytes::from(payload.clone()),
+            payload => Bytes::from(payload.to_string()),
+        }
+    }
+}
+
+/// Accepts a WebSocket connection, the token (if any) is expected either in
+/// `Authorization: Bearer <token>` or, for browsers, in `?token=<token>`.
+/// Returns the stream and the query parameters of the request.
+pub async fn accept(
+    stream: TcpStream,
+    token: Option<&Secret>,
+) -> Result<(WebSocketStream<TcpStream>, Vec<(String, String)>)> {
+    let mut query = Vec::new();
+    let callback = Handshake {
+        token,
+        query: &mut query,
+    };
+
+    let websocket = tokio_tungstenite::accept_hdr_async(stream, callback).await?;
+    Ok((websocket, query))
+}
+
+struct Handshake<'a> {
+    token: Option<&'a Secret>,
+    query: &'a mut Vec<(String, String)>,
+}
+
+impl Callback for Handshake<'_> {
+    fn on_request(
+        self,
+        request: &Request,
+        response: Response,
+    ) -> std::result::Result<Response, ErrorResponse> {
+        let pairs: Vec<(String, String)> = request
+            .uri()
+            .query()
+            .map(|query| {
+                form_urlencoded::parse(query.as_bytes())
+                    .into_owned()
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        if let Some(token) = self.token {
+            let bearer = format!("Bearer {}", token.expose());
+            let authorized = request
+                .headers()
+                .get(header::AUTHORIZATION)
+                .map(|value| value.as_bytes())
+                == Some(bearer.as_bytes())
+                || pairs
+                    .iter()
+                    .any(|(key, value)| key == "token" && value == token.expose());
+            if !authorized {
+                let mut error = ErrorResponse::new(None);
+                *error.status_mut() = StatusCode::UNAUTHORIZED;
+                return Err(error);
+            }
+        }
+
+        *self.query = pairs;
+        Ok(response)
+    }
+}
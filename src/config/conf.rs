@@ -1,19 +1,35 @@
 use std::collections::HashMap;
-use std::fs::{File, OpenOptions};
-use std::io::{Read, Write};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
 
-use crate::common::data::ElId;
+use crate::common::data::{ConnectionId, ElId};
+use crate::common::mqtt::MqttConnectionConfig;
 use crate::common::types::Result as AsyncResult;
+use crate::config::api::ApiConfig;
+use crate::config::format::ConfigFormat;
 use crate::config::handler::HandlerConfig;
+use crate::config::health::HealthConfig;
 use crate::config::input::InputConfig;
+use crate::config::leader::LeaderElectionConfig;
+use crate::config::loader::ConfigLoader;
 use crate::config::output::OutputConfig;
-use log::debug;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Default, PartialEq, Serialize, Deserialize, Clone)]
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize, Clone, JsonSchema)]
 #[serde(deny_unknown_fields)]
 #[serde(default)]
 pub struct Config {
+    /// Other config files (paths or glob patterns relative to this file) to merge into this one
+    #[serde(default, skip_serializing_if = "OneOrMany::is_empty")]
+    pub include: OneOrMany<String>,
+
+    /// MQTT connections shared by inputs and outputs referring to them
+    #[serde(rename = "connection")]
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub connections: HashMap<ConnectionId, MqttConnectionConfig>,
+
     #[serde(rename = "input")]
     #[serde(default)]
     pub inputs: HashMap<ElId, InputConfig>,
@@ -25,35 +41,139 @@ pub struct Config {
     #[serde(rename = "handler")]
     #[serde(default)]
     pub handlers: Vec<HandlerConfig>,
+
+    #[serde(default)]
+    pub api: Option<ApiConfig>,
+
+    #[serde(default)]
+    pub health: HealthConfig,
+
+    /// Only the elected instance executes actions, the others stand by
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub leader_election: Option<LeaderElectionConfig>,
+}
+
+/// A single value or a list of them, e.g. `include = "conf.d/*.toml"`
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(untagged)]
+pub enum OneOrMany<T> {
+    One(T),
+    Many(Vec<T>),
+}
+
+impl<T> OneOrMany<T> {
+    pub fn is_empty(&self) -> bool {
+        match self {
+            OneOrMany::One(_) => false,
+            OneOrMany::Many(values) => values.is_empty(),
+        }
+    }
+
+    pub fn into_vec(self) -> Vec<T> {
+        match self {
+            OneOrMany::One(value) => vec![value],
+            OneOrMany::Many(values) => values,
+        }
+    }
+}
+
+impl<T> Default for OneOrMany<T> {
+    fn default() -> Self {
+        OneOrMany::Many(Vec::new())
+    }
 }
 
 impl Config {
-    pub(crate) fn load(config_file: &str) -> AsyncResult<Self> {
-        let mut data = String::new();
+    /// Loads config from a file or from every config file in a directory, following `include = [...]`
+    pub(crate) fn load(config_path: &str, format: Option<ConfigFormat>) -> AsyncResult<Self> {
+        ConfigLoader::load(config_path, format)
+    }
+
+    /// Same as `load`, but keeps environment variables and secret files unresolved
+    pub(crate) fn load_unresolved(
+        config_path: &str,
+        format: Option<ConfigFormat>,
+    ) -> AsyncResult<Self> {
+        ConfigLoader::load_unresolved(config_path, format)
+    }
 
-        debug!("Reading config from {:?}", config_file);
+    pub(crate) fn resolve_secrets(&mut self) -> AsyncResult<()> {
+        for (id, connection) in self.connections.iter_mut() {
+            connection
+                .resolve_secrets()
+                .map_err(|err| format!("Connection[{}]: {}", id, err))?;
+        }
 
-        let mut file = File::open(config_file)?;
-        file.read_to_string(&mut data)?;
+        for (id, input) in self.inputs.iter_mut() {
+            input
+                .resolve_secrets()
+                .map_err(|err| format!("Input[{}]: {}", id, err))?;
+        }
 
-        let config: Config = toml::from_str(&data)?;
+        for (id, output) in self.outputs.iter_mut() {
+            output
+                .resolve_secrets()
+                .map_err(|err| format!("Output[{}]: {}", id, err))?;
+        }
+
+        if let Some(leader_election) = self.leader_election.as_mut() {
+            leader_election
+                .resolve_secrets()
+                .map_err(|err| format!("[leader_election]: {}", err))?;
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn validate(&self) -> AsyncResult<()> {
+        for (id, connection) in &self.connections {
+            connection
+                .validate()
+                .map_err(|err| format!("Connection[{}]: {}", id, err))?;
+        }
+
+        for (id, input) in &self.inputs {
+            input
+                .validate()
+                .map_err(|err| format!("Input[{}]: {}", id, err))?;
+        }
+
+        for (id, output) in &self.outputs {
+            output
+                .validate()
+                .map_err(|err| format!("Output[{}]: {}", id, err))?;
+        }
+
+        if let Some(leader_election) = &self.leader_election {
+            leader_election
+                .validate()
+                .map_err(|err| format!("[leader_election]: {}", err))?;
+        }
+
+        Ok(())
+    }
 
-        Ok(config)
+    /// JSON Schema describing the config (the same for all formats)
+    pub fn json_schema() -> AsyncResult<String> {
+        let schema = schemars::schema_for!(Config);
+        Ok(serde_json::to_string_pretty(&schema)?)
     }
 
-    pub fn dump_to_string(&self) -> AsyncResult<String> {
-        let data = toml::to_string(&self)?;
-        Ok(data)
+    pub fn dump_to_string(&self, format: ConfigFormat) -> AsyncResult<String> {
+        format.dump(self)
     }
 
-    pub fn dump_to_file(&self, config_file: &str) -> AsyncResult<()> {
-        let data = toml::to_string(&self)?;
+    /// Writes config to the file, format is detected by the extension unless given explicitly
+    pub fn dump_to_file(&self, config_file: &str, format: Option<ConfigFormat>) -> AsyncResult<()> {
+        let format = format
+            .or_else(|| ConfigFormat::from_path(Path::new(config_file)))
+            .unwrap_or_default();
+        let data = self.dump_to_string(format)?;
         let mut file = OpenOptions::new()
             .write(true)
             .create(true)
             .truncate(true)
-            .open(config_file)
-            .unwrap();
+            .open(config_file)?;
         file.write_all(data.as_bytes())?;
         Ok(())
     }
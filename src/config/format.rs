@@ -0,0 +1,89 @@
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+use std::str::FromStr;
+
+use serde::Serialize;
+
+use crate::common::types::Result;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigFormat {
+    Toml,
+    Yaml,
+    Json,
+}
+
+impl ConfigFormat {
+    pub fn from_path(path: &Path) -> Option<Self> {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .and_then(|ext| ext.parse().ok())
+    }
+
+    /// Parses any format into a TOML tree, so that includes and interpolation work the same way
+    pub fn parse(&self, data: &str) -> Result<toml::Value> {
+        let value = match self {
+            ConfigFormat::Toml => return Ok(toml::from_str(data)?),
+            ConfigFormat::Yaml => serde_yaml::from_str(data)?,
+            ConfigFormat::Json => serde_json::from_str(data)?,
+        };
+
+        Ok(toml::Value::try_from(without_nulls(value))?)
+    }
+
+    pub fn dump<T: Serialize>(&self, value: &T) -> Result<String> {
+        let data = match self {
+            ConfigFormat::Toml => toml::to_string(&toml::Value::try_from(value)?)?,
+            ConfigFormat::Yaml => {
+                serde_yaml::to_string(&without_nulls(serde_json::to_value(value)?))?
+            }
+            ConfigFormat::Json => {
+                serde_json::to_string_pretty(&without_nulls(serde_json::to_value(value)?))?
+            }
+        };
+
+        Ok(data)
+    }
+}
+
+impl Default for ConfigFormat {
+    fn default() -> Self {
+        ConfigFormat::Toml
+    }
+}
+
+impl FromStr for ConfigFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "toml" => Ok(ConfigFormat::Toml),
+            "yaml" | "yml" => Ok(ConfigFormat::Yaml),
+            "json" => Ok(ConfigFormat::Json),
+            other => Err(format!("Unknown config format {:?}", other)),
+        }
+    }
+}
+
+impl Display for ConfigFormat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigFormat::Toml => write!(f, "toml"),
+            ConfigFormat::Yaml => write!(f, "yaml"),
+            ConfigFormat::Json => write!(f, "json"),
+        }
+    }
+}
+
+// TOML has no null, so unset options are dropped instead
+fn without_nulls(value: serde_json::Value) -> serde_json::Value {
+    match value {
+        serde_json::Value::Object(map) => map
+            .into_iter()
+            .filter(|(_, value)| !value.is_null())
+            .map(|(key, value)| (key, without_nulls(value)))
+            .collect(),
+        serde_json::Value::Array(values) => values.into_iter().map(without_nulls).collect(),
+        value => value,
+    }
+}
@@ -1,7 +1,8 @@
 use crate::common::data::ElId;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, JsonSchema)]
 pub struct HandlerTriggerConfig {
     #[serde(rename = "input")]
     pub input_id: ElId,
@@ -9,7 +10,7 @@ pub struct HandlerTriggerConfig {
     pub trigger_id: ElId,
 }
 
-#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, JsonSchema)]
 pub struct HandlerActionConfig {
     #[serde(rename = "output")]
     pub output_id: ElId,
@@ -17,7 +18,7 @@ pub struct HandlerActionConfig {
     pub action_id: ElId,
 }
 
-#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, JsonSchema)]
 pub struct HandlerConfig {
     #[serde(rename = "on")]
     pub trigger: HandlerTriggerConfig,
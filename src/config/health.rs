@@ -0,0 +1,35 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(deny_unknown_fields)]
+#[serde(default)]
+pub struct HealthConfig {
+    /// How long an input/output may stay disconnected before mqrt is reported unhealthy
+    pub disconnected_timeout_secs: u64,
+    /// How long a dispatcher route may stay without a heartbeat before it is considered wedged
+    pub stalled_timeout_secs: u64,
+    /// Send READY/WATCHDOG notifications to systemd (no-op when not started by systemd)
+    pub systemd_notify: bool,
+}
+
+impl HealthConfig {
+    pub fn disconnected_timeout(&self) -> Duration {
+        Duration::from_secs(self.disconnected_timeout_secs)
+    }
+
+    pub fn stalled_timeout(&self) -> Duration {
+        Duration::from_secs(self.stalled_timeout_secs)
+    }
+}
+
+impl Default for HealthConfig {
+    fn default() -> Self {
+        Self {
+            disconnected_timeout_secs: 60,
+            stalled_timeout_secs: 30,
+            systemd_notify: true,
+        }
+    }
+}
@@ -1,8 +1,100 @@
+use crate::common::data::{ConnectionId, TriggerId};
+use crate::common::types::Result;
+use crate::inputs::amqp::AmqpInputConfig;
+use crate::inputs::file::FileInputConfig;
+use crate::inputs::http::HttpInputConfig;
+use crate::inputs::internal::InternalInputConfig;
+#[cfg(feature = "kafka")]
+use crate::inputs::kafka::KafkaInputConfig;
+use crate::inputs::lifecycle::LifecycleInputConfig;
 use crate::inputs::mqtt::MqttInputConfig;
+use crate::inputs::redis::RedisInputConfig;
+use crate::inputs::schedule::ScheduleInputConfig;
+use crate::inputs::socket::{UdpInputConfig, UnixSocketInputConfig};
+use crate::inputs::websocket::WebSocketInputConfig;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, JsonSchema)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum InputConfig {
     Mqtt(MqttInputConfig),
+    Http(HttpInputConfig),
+    Schedule(ScheduleInputConfig),
+    File(FileInputConfig),
+    Udp(UdpInputConfig),
+    UnixSocket(UnixSocketInputConfig),
+    #[serde(rename = "websocket")]
+    WebSocket(WebSocketInputConfig),
+    Redis(RedisInputConfig),
+    Amqp(AmqpInputConfig),
+    Internal(InternalInputConfig),
+    Lifecycle(LifecycleInputConfig),
+    #[cfg(feature = "kafka")]
+    Kafka(KafkaInputConfig),
+}
+
+impl InputConfig {
+    pub fn trigger_ids(&self) -> Vec<TriggerId> {
+        match self {
+            InputConfig::Mqtt(config) => config.triggers.keys().cloned().collect(),
+            InputConfig::Http(config) => config.triggers.keys().cloned().collect(),
+            InputConfig::Schedule(config) => config.triggers.keys().cloned().collect(),
+            InputConfig::File(config) => config.triggers.keys().cloned().collect(),
+            InputConfig::Udp(config) => config.triggers.keys().cloned().collect(),
+            InputConfig::UnixSocket(config) => config.triggers.keys().cloned().collect(),
+            InputConfig::WebSocket(config) => config.triggers.keys().cloned().collect(),
+            InputConfig::Redis(config) => config.triggers.keys().cloned().collect(),
+            InputConfig::Amqp(config) => config.triggers.keys().cloned().collect(),
+            InputConfig::Internal(config) => config.triggers.keys().cloned().collect(),
+            InputConfig::Lifecycle(config) => config.triggers.keys().cloned().collect(),
+            #[cfg(feature = "kafka")]
+            InputConfig::Kafka(config) => config.triggers.keys().cloned().collect(),
+        }
+    }
+
+    pub fn resolve_secrets(&mut self) -> Result<()> {
+        match self {
+            InputConfig::Mqtt(config) => config.resolve_secrets(),
+            InputConfig::Redis(config) => config.connection.resolve_secrets(),
+            InputConfig::Amqp(config) => config.connection.resolve_secrets(),
+            #[cfg(feature = "kafka")]
+            InputConfig::Kafka(config) => config.connection.resolve_secrets(),
+            InputConfig::Http(_)
+            | InputConfig::Schedule(_)
+            | InputConfig::File(_)
+            | InputConfig::Udp(_)
+            | InputConfig::UnixSocket(_)
+            | InputConfig::WebSocket(_)
+            | InputConfig::Internal(_)
+            | InputConfig::Lifecycle(_) => Ok(()),
+        }
+    }
+
+    /// Shared connection the input refers to
+    pub fn connection_id(&self) -> Option<&ConnectionId> {
+        match self {
+            InputConfig::Mqtt(config) => config.connection.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// Checks values that serde can not, e.g. cron expressions
+    pub fn validate(&self) -> Result<()> {
+        match self {
+            InputConfig::Schedule(config) => config.validate(),
+            InputConfig::File(config) => config.validate(),
+            InputConfig::UnixSocket(config) => config.validate(),
+            InputConfig::Redis(config) => config.validate(),
+            InputConfig::Amqp(config) => config.validate(),
+            InputConfig::Lifecycle(config) => config.validate(),
+            InputConfig::Mqtt(config) => config.validate(),
+            #[cfg(feature = "kafka")]
+            InputConfig::Kafka(_) => Ok(()),
+            InputConfig::Http(_)
+            | InputConfig::Udp(_)
+            | InputConfig::WebSocket(_)
+            | InputConfig::Internal(_) => Ok(()),
+        }
+    }
 }
@@ -0,0 +1,132 @@
+use std::env;
+
+use crate::common::types::Result;
+
+/// Replaces `${VAR}` and `${VAR:-default}` in every string value with environment variables,
+/// `$$` is an escaped `$`. JavaScript `code` is kept as is, `${...}` there is a template literal.
+pub fn interpolate_env(value: &mut toml::Value) -> Result<()> {
+    match value {
+        toml::Value::String(s) => {
+            *s = interpolate_str(s)?;
+        }
+        toml::Value::Array(values) => {
+            for value in values.iter_mut() {
+                interpolate_env(value)?;
+            }
+        }
+        toml::Value::Table(table) => {
+            let is_js = table.get("type").and_then(|x| x.as_str()) == Some("js");
+            for (key, value) in table.iter_mut() {
+                if is_js && key == "code" {
+                    continue;
+                }
+                interpolate_env(value)?;
+            }
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+fn interpolate_str(input: &str) -> Result<String> {
+    let mut result = String::with_capacity(input.len());
+    let mut rest = input;
+
+    while let Some(start) = rest.find('$') {
+        result.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        if rest.starts_with("$$") {
+            result.push('$');
+            rest = &rest[2..];
+        } else if rest.starts_with("${") {
+            let end = rest
+                .find('}')
+                .ok_or_else(|| format!("Unclosed variable reference in {:?}", input))?;
+            let reference = &rest[2..end];
+            let (name, default) = match reference.find(":-") {
+                Some(pos) => (&reference[..pos], Some(&reference[pos + 2..])),
+                None => (reference, None),
+            };
+
+            match (env::var(name), default) {
+                (Ok(value), _) => result.push_str(&value),
+                (Err(_), Some(default)) => result.push_str(default),
+                (Err(err), None) => {
+                    return Err(format!("Can not resolve ${{{}}}: {}", name, err).into())
+                }
+            }
+            rest = &rest[end + 1..];
+        } else {
+            result.push('$');
+            rest = &rest[1..];
+        }
+    }
+    result.push_str(rest);
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replaces_variables_and_defaults() {
+        env::set_var("MQRT_TEST_INTERPOLATE_HOST", "broker");
+        env::remove_var("MQRT_TEST_INTERPOLATE_MISSING");
+
+        assert_eq!(
+            interpolate_str("tcp://${MQRT_TEST_INTERPOLATE_HOST}:1883").unwrap(),
+            "tcp://broker:1883"
+        );
+        assert_eq!(
+            interpolate_str("${MQRT_TEST_INTERPOLATE_MISSING:-localhost}").unwrap(),
+            "localhost"
+        );
+        assert_eq!(
+            interpolate_str("${MQRT_TEST_INTERPOLATE_HOST:-localhost}").unwrap(),
+            "broker"
+        );
+        assert!(interpolate_str("${MQRT_TEST_INTERPOLATE_MISSING}").is_err());
+    }
+
+    #[test]
+    fn unescapes_double_dollar() {
+        assert_eq!(interpolate_str("price: $$5").unwrap(), "price: $5");
+        assert_eq!(interpolate_str("$${HOME}").unwrap(), "${HOME}");
+    }
+
+    #[test]
+    fn rejects_unclosed_reference() {
+        assert!(interpolate_str("${HOME").is_err());
+    }
+
+    #[test]
+    fn keeps_lone_dollar() {
+        assert_eq!(
+            interpolate_str("$share/group/topic").unwrap(),
+            "$share/group/topic"
+        );
+        assert_eq!(interpolate_str("costs 5$").unwrap(), "costs 5$");
+    }
+
+    #[test]
+    fn skips_js_code() {
+        let mut value: toml::Value = toml::from_str(
+            r#"
+            filter = { type = "js", code = 'return `${payload}` == "on$$"' }
+            payload = { type = "static", data = "$$" }
+            "#,
+        )
+        .unwrap();
+        interpolate_env(&mut value).unwrap();
+
+        assert_eq!(
+            value["filter"]["code"].as_str(),
+            Some(r#"return `${payload}` == "on$$""#)
+        );
+        assert_eq!(value["payload"]["data"].as_str(), Some("$"));
+    }
+}
@@ -0,0 +1,74 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::common::data::ConnectionId;
+use crate::common::mqtt::MqttConnectionConfig;
+use crate::common::types::Result;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct LeaderElectionConfig {
+    /// Retained topic holding the id of the current leader
+    #[serde(default = "default_topic")]
+    pub topic: String,
+    /// Unique id of this instance, the host name by default
+    pub instance: Option<String>,
+    /// How long a standby waits for the lock to be taken before claiming it
+    #[serde(default = "default_takeover_secs")]
+    pub takeover_secs: u64,
+    /// Id of a `[connection.<id>]` to take the broker settings from, instead of own ones
+    pub connection: Option<ConnectionId>,
+    /// Broker settings; `keep_alive_secs` is the one of the election client, with `connection` too,
+    /// and defaults to 5, a gone leader is noticed by the broker after 1.5 times of it
+    #[serde(flatten)]
+    pub settings: MqttConnectionConfig,
+}
+
+fn default_topic() -> String {
+    "mqrt/leader".to_string()
+}
+
+fn default_takeover_secs() -> u64 {
+    2
+}
+
+const DEFAULT_KEEP_ALIVE_SECS: u64 = 5;
+
+impl LeaderElectionConfig {
+    pub fn resolve_secrets(&mut self) -> Result<()> {
+        self.settings.resolve_secrets()
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if self.topic.is_empty() || self.topic.contains(&['+', '#'][..]) {
+            return Err(format!("Invalid `topic` {:?}", self.topic).into());
+        }
+        if self.settings.has_status_messages() {
+            return Err(
+                "`last_will` and `birth` can not be set, the lock uses the Last Will".into(),
+            );
+        }
+        if self.keep_alive_secs() == 0 {
+            return Err("`keep_alive_secs` must be positive".into());
+        }
+
+        match self.connection {
+            Some(_) if !self.settings.with_keep_alive(None).is_empty() => Err(
+                "Connection settings other than `keep_alive_secs` can not be set along with `connection`"
+                    .into(),
+            ),
+            Some(_) => Ok(()),
+            None => self.settings.validate(),
+        }
+    }
+
+    pub fn keep_alive_secs(&self) -> u64 {
+        self.settings
+            .keep_alive_secs()
+            .unwrap_or(DEFAULT_KEEP_ALIVE_SECS)
+    }
+
+    pub fn takeover(&self) -> Duration {
+        Duration::from_secs(self.takeover_secs)
+    }
+}
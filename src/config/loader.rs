@@ -0,0 +1,305 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use log::debug;
+
+use crate::common::data::ElId;
+use crate::common::types::Result;
+use crate::config::format::ConfigFormat;
+use crate::config::interpolate::interpolate_env;
+use crate::config::Config;
+
+/// Loads a config from a single file or from every config file in a directory, following
+/// `include = [...]` directives and remembering which file every element comes from
+#[derive(Debug, Default)]
+pub struct ConfigLoader {
+    resolve: bool,
+    config: Config,
+    visited: HashSet<PathBuf>,
+    /// Files being loaded, from the top one down to the current include
+    stack: Vec<PathBuf>,
+    connections: HashMap<ElId, PathBuf>,
+    inputs: HashMap<ElId, PathBuf>,
+    outputs: HashMap<ElId, PathBuf>,
+    handlers: Vec<PathBuf>,
+    api: Option<PathBuf>,
+    health: Option<PathBuf>,
+    leader_election: Option<PathBuf>,
+}
+
+impl ConfigLoader {
+    /// Loads the config and resolves environment variables and secrets
+    pub fn load(path: &str, format: Option<ConfigFormat>) -> Result<Config> {
+        Self::load_with(path, format, true)
+    }
+
+    /// Loads the config as written, e.g. to convert it into another format
+    pub fn load_unresolved(path: &str, format: Option<ConfigFormat>) -> Result<Config> {
+        Self::load_with(path, format, false)
+    }
+
+    fn load_with(path: &str, format: Option<ConfigFormat>, resolve: bool) -> Result<Config> {
+        let mut loader = Self {
+            resolve,
+            ..Self::default()
+        };
+        loader.load_path(Path::new(path), format)?;
+        loader.validate()?;
+
+        Ok(loader.config)
+    }
+
+    fn load_path(&mut self, path: &Path, format: Option<ConfigFormat>) -> Result<()> {
+        if path.is_dir() {
+            for file in config_files_in(path)? {
+                self.load_file(&file, None)?;
+            }
+            Ok(())
+        } else {
+            self.load_file(path, format)
+        }
+    }
+
+    fn load_file(&mut self, path: &Path, format: Option<ConfigFormat>) -> Result<()> {
+        let canonical = path
+            .canonicalize()
+            .map_err(|err| format!("Can not read {}: {}", path.display(), err))?;
+        if self.stack.contains(&canonical) {
+            return Err(format!("{} includes itself", path.display()).into());
+        }
+        if !self.visited.insert(canonical.clone()) {
+            debug!("Skipping {:?}, it is already loaded", path);
+            return Ok(());
+        }
+
+        debug!("Reading config from {:?}", path);
+
+        let in_file = |err: &dyn std::fmt::Display| format!("{}: {}", path.display(), err);
+
+        let format = format
+            .or_else(|| ConfigFormat::from_path(path))
+            .unwrap_or_default();
+
+        let data = fs::read_to_string(path).map_err(|err| in_file(&err))?;
+        let mut value = format.parse(&data).map_err(|err| in_file(&err))?;
+        if self.resolve {
+            interpolate_env(&mut value).map_err(|err| in_file(&err))?;
+        }
+
+        let defines_api = value.get("api").is_some();
+        let defines_health = value.get("health").is_some();
+
+        let mut config: Config = value.try_into().map_err(|err| in_file(&err))?;
+        if self.resolve {
+            config.resolve_secrets().map_err(|err| in_file(&err))?;
+            config.validate().map_err(|err| in_file(&err))?;
+        }
+        let includes = std::mem::take(&mut config.include).into_vec();
+
+        self.merge(path, config, defines_api, defines_health)?;
+
+        self.stack.push(canonical);
+        let base_dir = path.parent().unwrap_or_else(|| Path::new("."));
+        for include in includes {
+            for included in resolve_include(base_dir, &include).map_err(|err| in_file(&err))? {
+                self.load_path(&included, None)?;
+            }
+        }
+        self.stack.pop();
+
+        Ok(())
+    }
+
+    fn merge(
+        &mut self,
+        path: &Path,
+        config: Config,
+        defines_api: bool,
+        defines_health: bool,
+    ) -> Result<()> {
+        for (id, connection) in config.connections {
+            if let Some(previous) = self.connections.insert(id.clone(), path.to_path_buf()) {
+                return Err(duplicate_error(
+                    &format!("Connection[{}]", id),
+                    &previous,
+                    path,
+                ));
+            }
+            self.config.connections.insert(id, connection);
+        }
+
+        for (id, input) in config.inputs {
+            if let Some(previous) = self.inputs.insert(id.clone(), path.to_path_buf()) {
+                return Err(duplicate_error(&format!("Input[{}]", id), &previous, path));
+            }
+            self.config.inputs.insert(id, input);
+        }
+
+        for (id, output) in config.outputs {
+            if let Some(previous) = self.outputs.insert(id.clone(), path.to_path_buf()) {
+                return Err(duplicate_error(&format!("Output[{}]", id), &previous, path));
+            }
+            self.config.outputs.insert(id, output);
+        }
+
+        for handler in config.handlers {
+            self.handlers.push(path.to_path_buf());
+            self.config.handlers.push(handler);
+        }
+
+        if defines_api {
+            if let Some(previous) = self.api.replace(path.to_path_buf()) {
+                return Err(duplicate_error("[api]", &previous, path));
+            }
+            self.config.api = config.api;
+        }
+
+        if defines_health {
+            if let Some(previous) = self.health.replace(path.to_path_buf()) {
+                return Err(duplicate_error("[health]", &previous, path));
+            }
+            self.config.health = config.health;
+        }
+
+        if config.leader_election.is_some() {
+            if let Some(previous) = self.leader_election.replace(path.to_path_buf()) {
+                return Err(duplicate_error("[leader_election]", &previous, path));
+            }
+            self.config.leader_election = config.leader_election;
+        }
+
+        Ok(())
+    }
+
+    fn validate(&self) -> Result<()> {
+        let mut errors = Vec::new();
+
+        let inputs = self.config.inputs.iter().map(|(id, input)| {
+            (
+                format!("Input[{}]", id),
+                input.connection_id(),
+                &self.inputs[id],
+            )
+        });
+        let outputs = self.config.outputs.iter().map(|(id, output)| {
+            (
+                format!("Output[{}]", id),
+                output.connection_id(),
+                &self.outputs[id],
+            )
+        });
+        let leader_election = self.config.leader_election.iter().map(|config| {
+            let path = self.leader_election.as_ref().unwrap();
+            (
+                "[leader_election]".to_string(),
+                config.connection.as_ref(),
+                path,
+            )
+        });
+        for (what, connection_id, path) in inputs.chain(outputs).chain(leader_election) {
+            if let Some(connection_id) = connection_id {
+                if !self.config.connections.contains_key(connection_id) {
+                    errors.push(format!(
+                        "{}: {} refers to unknown Connection[{}]",
+                        path.display(),
+                        what,
+                        connection_id
+                    ));
+                }
+            }
+        }
+
+        for ((index, handler), path) in self.config.handlers.iter().enumerate().zip(&self.handlers)
+        {
+            let input_id = &handler.trigger.input_id;
+            let trigger_id = &handler.trigger.trigger_id;
+            let output_id = &handler.action.output_id;
+            let action_id = &handler.action.action_id;
+
+            match self.config.inputs.get(input_id) {
+                None => errors.push(format!(
+                    "{}: handler #{} refers to unknown Input[{}]",
+                    path.display(),
+                    index,
+                    input_id
+                )),
+                Some(input) if !input.trigger_ids().contains(trigger_id) => errors.push(format!(
+                    "{}: handler #{} refers to unknown Trigger[{}::{}] (input is defined in {})",
+                    path.display(),
+                    index,
+                    input_id,
+                    trigger_id,
+                    self.inputs[input_id].display()
+                )),
+                _ => {}
+            }
+
+            match self.config.outputs.get(output_id) {
+                None => errors.push(format!(
+                    "{}: handler #{} refers to unknown Output[{}]",
+                    path.display(),
+                    index,
+                    output_id
+                )),
+                Some(output) if !output.action_ids().contains(action_id) => errors.push(format!(
+                    "{}: handler #{} refers to unknown Action[{}::{}] (output is defined in {})",
+                    path.display(),
+                    index,
+                    output_id,
+                    action_id,
+                    self.outputs[output_id].display()
+                )),
+                _ => {}
+            }
+        }
+
+        match errors.is_empty() {
+            true => Ok(()),
+            false => Err(errors.join("\n").into()),
+        }
+    }
+}
+
+fn resolve_include(base_dir: &Path, include: &str) -> Result<Vec<PathBuf>> {
+    let pattern = base_dir.join(include);
+    let pattern = pattern.to_string_lossy();
+
+    let mut paths = glob::glob(&pattern)
+        .map_err(|err| format!("Invalid include pattern {:?}: {}", include, err))?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    paths.sort();
+
+    if paths.is_empty() && !is_glob(include) {
+        return Err(format!("Included {} does not exist", pattern).into());
+    }
+
+    Ok(paths)
+}
+
+fn config_files_in(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = fs::read_dir(dir)
+        .map_err(|err| format!("Can not read {}: {}", dir.display(), err))?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<std::io::Result<Vec<_>>>()?
+        .into_iter()
+        .filter(|path| path.is_file() && ConfigFormat::from_path(path).is_some())
+        .collect::<Vec<_>>();
+    files.sort();
+
+    Ok(files)
+}
+
+fn is_glob(pattern: &str) -> bool {
+    pattern.contains(&['*', '?', '['][..])
+}
+
+fn duplicate_error(what: &str, previous: &Path, current: &Path) -> crate::common::types::Error {
+    format!(
+        "{} is defined in both {} and {}",
+        what,
+        previous.display(),
+        current.display()
+    )
+    .into()
+}
@@ -1,6 +1,12 @@
+pub mod api;
 mod conf;
+pub mod format;
 pub mod handler;
+pub mod health;
 pub mod input;
+mod interpolate;
+pub mod leader;
+mod loader;
 pub mod opt;
 pub mod output;
 
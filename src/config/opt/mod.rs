@@ -1,5 +1,7 @@
 use structopt::StructOpt;
 
+use crate::config::format::ConfigFormat;
+
 #[derive(StructOpt, Debug)]
 #[structopt(name = "env")]
 pub struct Opt {
@@ -12,6 +14,32 @@ pub struct Opt {
     )]
     pub config_path: String,
 
+    /// Config format (toml, yaml or json), detected by the file extension by default
+    #[structopt(long, env = "MQRT_CONFIG_FORMAT")]
+    pub format: Option<ConfigFormat>,
+
     #[structopt(short, long, env = "MQRT_THREADS")]
     pub threads: Option<usize>,
+
+    #[structopt(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(StructOpt, Debug, Clone)]
+pub enum Command {
+    /// Convert the config (with all includes merged) into another format
+    Convert {
+        /// Output file, `-` to print to stdout
+        output: String,
+
+        /// Output format, detected by the output file extension by default
+        #[structopt(long)]
+        to: Option<ConfigFormat>,
+    },
+
+    /// Print the JSON Schema of the config, e.g. for editor autocompletion and validation
+    Schema {
+        /// Output file, stdout by default
+        output: Option<String>,
+    },
 }
@@ -1,8 +1,95 @@
+use crate::common::data::{ActionId, ConnectionId};
+use crate::common::types::Result;
+use crate::outputs::amqp::AmqpOutputConfig;
+use crate::outputs::exec::ExecOutputConfig;
+use crate::outputs::file::FileOutputConfig;
+use crate::outputs::http::HttpOutputConfig;
+use crate::outputs::internal::InternalOutputConfig;
+#[cfg(feature = "kafka")]
+use crate::outputs::kafka::KafkaOutputConfig;
 use crate::outputs::mqtt::MqttOutputConfig;
+use crate::outputs::redis::RedisOutputConfig;
+use crate::outputs::sqlite::SqliteOutputConfig;
+use crate::outputs::stdout::StdoutOutputConfig;
+use crate::outputs::websocket::WebSocketOutputConfig;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, JsonSchema)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum OutputConfig {
     Mqtt(MqttOutputConfig),
+    Http(HttpOutputConfig),
+    Exec(ExecOutputConfig),
+    File(FileOutputConfig),
+    Stdout(StdoutOutputConfig),
+    #[serde(rename = "websocket")]
+    WebSocket(WebSocketOutputConfig),
+    Redis(RedisOutputConfig),
+    Amqp(AmqpOutputConfig),
+    Sqlite(SqliteOutputConfig),
+    Internal(InternalOutputConfig),
+    #[cfg(feature = "kafka")]
+    Kafka(KafkaOutputConfig),
+}
+
+impl OutputConfig {
+    pub fn action_ids(&self) -> Vec<ActionId> {
+        match self {
+            OutputConfig::Mqtt(config) => config.actions.keys().cloned().collect(),
+            OutputConfig::Http(config) => config.actions.keys().cloned().collect(),
+            OutputConfig::Exec(config) => config.actions.keys().cloned().collect(),
+            OutputConfig::File(config) => config.actions.keys().cloned().collect(),
+            OutputConfig::Stdout(config) => config.actions.keys().cloned().collect(),
+            OutputConfig::WebSocket(config) => config.actions.keys().cloned().collect(),
+            OutputConfig::Redis(config) => config.actions.keys().cloned().collect(),
+            OutputConfig::Amqp(config) => config.actions.keys().cloned().collect(),
+            OutputConfig::Sqlite(config) => config.actions.keys().cloned().collect(),
+            OutputConfig::Internal(config) => config.actions.keys().cloned().collect(),
+            #[cfg(feature = "kafka")]
+            OutputConfig::Kafka(config) => config.actions.keys().cloned().collect(),
+        }
+    }
+
+    pub fn resolve_secrets(&mut self) -> Result<()> {
+        match self {
+            OutputConfig::Mqtt(config) => config.resolve_secrets(),
+            OutputConfig::Redis(config) => config.connection.resolve_secrets(),
+            OutputConfig::Amqp(config) => config.connection.resolve_secrets(),
+            #[cfg(feature = "kafka")]
+            OutputConfig::Kafka(config) => config.connection.resolve_secrets(),
+            OutputConfig::Http(_)
+            | OutputConfig::Exec(_)
+            | OutputConfig::File(_)
+            | OutputConfig::Stdout(_)
+            | OutputConfig::Sqlite(_)
+            | OutputConfig::Internal(_)
+            | OutputConfig::WebSocket(_) => Ok(()),
+        }
+    }
+
+    /// Shared connection the output refers to
+    pub fn connection_id(&self) -> Option<&ConnectionId> {
+        match self {
+            OutputConfig::Mqtt(config) => config.connection.as_ref(),
+            _ => None,
+        }
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        match self {
+            OutputConfig::Mqtt(config) => config.validate(),
+            OutputConfig::Amqp(config) => config.connection.validate(),
+            #[cfg(feature = "kafka")]
+            OutputConfig::Kafka(_) => Ok(()),
+            OutputConfig::Http(_)
+            | OutputConfig::Exec(_)
+            | OutputConfig::File(_)
+            | OutputConfig::Stdout(_)
+            | OutputConfig::WebSocket(_)
+            | OutputConfig::Redis(_)
+            | OutputConfig::Sqlite(_)
+            | OutputConfig::Internal(_) => Ok(()),
+        }
+    }
 }
@@ -0,0 +1,41 @@
+use bytes::Bytes;
+use log::trace;
+use tokio::sync::broadcast;
+
+/// Event sent by an `internal` output to `internal` inputs
+#[derive(Debug, Clone)]
+pub struct BusEvent {
+    pub channel: String,
+    /// How many internal hops the event has made, used to stop cycles
+    pub depth: u32,
+    pub payload: Bytes,
+}
+
+/// In-process bus chaining outputs to inputs without a round trip through the broker
+#[derive(Debug, Clone)]
+pub struct InternalBus {
+    sender: broadcast::Sender<BusEvent>,
+}
+
+impl InternalBus {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(128);
+        Self { sender }
+    }
+
+    pub fn publish(&self, event: BusEvent) {
+        if let Err(err) = self.sender.send(event) {
+            trace!("No internal inputs for {:?}", err.0.channel);
+        }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<BusEvent> {
+        self.sender.subscribe()
+    }
+}
+
+impl Default for InternalBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -2,22 +2,28 @@ use crate::common::data::{
     ActionId, ActionableEvent, ElId, InputId, OutputId, TriggerId, TriggeredEvent,
 };
 use std::collections::HashMap;
+use std::time::Duration;
 
 use crate::config::handler::HandlerConfig;
+use crate::config::Config;
+use crate::coordinator::{DispatcherControl, Leadership};
+use crate::health::{ComponentKind, HealthRegistry};
 use tokio::sync::mpsc;
 
 use futures::future::join_all;
-use itertools::Itertools;
 use log::{error, trace};
 #[allow(unused_imports)]
 use tokio_stream::StreamExt;
 
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(5);
+
 type RoutesByTriggerIdMap =
-    HashMap<TriggerId, Vec<(mpsc::Sender<ActionableEvent>, OutputId, ActionId)>>;
+    HashMap<TriggerId, Vec<(usize, mpsc::Sender<ActionableEvent>, OutputId, ActionId)>>;
 
 #[derive(Debug, Default)]
 pub struct ChannelDispatcher {
     inputs: HashMap<InputId, mpsc::Receiver<TriggeredEvent>>,
+    input_senders: HashMap<InputId, mpsc::Sender<TriggeredEvent>>,
     outputs: HashMap<OutputId, mpsc::Sender<ActionableEvent>>,
 }
 
@@ -29,6 +35,7 @@ impl ChannelDispatcher {
     pub fn create_channel_for_input(&mut self, id: &ElId) -> mpsc::Sender<TriggeredEvent> {
         let (tx, rx) = mpsc::channel(128);
         self.inputs.insert(id.clone(), rx);
+        self.input_senders.insert(id.clone(), tx.clone());
 
         tx
     }
@@ -40,15 +47,31 @@ impl ChannelDispatcher {
         rx
     }
 
-    pub async fn run_handlers(self, handlers: Vec<HandlerConfig>) {
+    pub fn control(&self, config: &Config, leadership: Leadership) -> DispatcherControl {
+        DispatcherControl::new(
+            config,
+            leadership,
+            self.input_senders.clone(),
+            self.outputs.clone(),
+        )
+    }
+
+    pub async fn run_handlers(
+        self,
+        handlers: Vec<HandlerConfig>,
+        health: HealthRegistry,
+        control: DispatcherControl,
+    ) {
         for (input_id, mut input_rx) in self.inputs.into_iter() {
-            // Useless conversion is allowed here due to issue with Rust JetBrains extension :(
-            #[allow(clippy::useless_conversion)]
-            let actions_by_trigger: RoutesByTriggerIdMap = handlers
-                .clone()
+            let route_health = health.register(ComponentKind::Dispatcher, &input_id);
+            let control = control.clone();
+
+            let mut actions_by_trigger = RoutesByTriggerIdMap::new();
+            handlers
                 .iter()
-                .filter(|conf| conf.trigger.input_id == input_id)
-                .map(|conf| {
+                .enumerate()
+                .filter(|(_, conf)| conf.trigger.input_id == input_id)
+                .for_each(|(index, conf)| {
                     trace!(
                         "Found route from Trigger[{}::{}] to Action[{}::{}]",
                         conf.trigger.input_id,
@@ -56,38 +79,33 @@ impl ChannelDispatcher {
                         conf.action.output_id,
                         conf.action.action_id
                     );
-                    conf
-                })
-                .group_by(|conf| conf.trigger.trigger_id.clone())
-                .into_iter()
-                .map(|(trigger_id, group)| {
-                    (
-                        trigger_id,
-                        group
-                            .map(|conf| {
-                                (conf.action.output_id.clone(), conf.action.action_id.clone())
-                            })
-                            .map(|(output_id, action_id)| {
-                                (
-                                    self.outputs
-                                        .get(&output_id)
-                                        .unwrap_or_else(|| {
-                                            panic!("Can not find output with id={}", &output_id)
-                                        })
-                                        .clone(),
-                                    output_id,
-                                    action_id,
-                                )
-                            })
-                            .collect(),
-                    )
-                })
-                .collect::<RoutesByTriggerIdMap>()
-                .try_into()
-                .unwrap();
+                    let output_id = conf.action.output_id.clone();
+                    let tx = self
+                        .outputs
+                        .get(&output_id)
+                        .unwrap_or_else(|| panic!("Can not find output with id={}", &output_id))
+                        .clone();
+
+                    actions_by_trigger
+                        .entry(conf.trigger.trigger_id.clone())
+                        .or_default()
+                        .push((index, tx, output_id, conf.action.action_id.clone()));
+                });
 
             tokio::spawn(async move {
-                while let Some(triggered_event) = input_rx.recv().await {
+                let mut heartbeat = tokio::time::interval(HEARTBEAT_INTERVAL);
+                loop {
+                    let triggered_event = tokio::select! {
+                        _ = heartbeat.tick() => {
+                            route_health.heartbeat();
+                            continue;
+                        }
+                        received = input_rx.recv() => match received {
+                            Some(triggered_event) => triggered_event,
+                            None => break,
+                        },
+                    };
+
                     trace!(
                         "Router received event from Trigger[{}::{}]",
                         triggered_event.input,
@@ -95,10 +113,29 @@ impl ChannelDispatcher {
                     );
                     let trigger_id = &triggered_event.trigger;
 
+                    if !control.accept_trigger(&triggered_event.input, trigger_id) {
+                        trace!(
+                            "Trigger[{}::{}] is disabled, skipping event",
+                            triggered_event.input,
+                            triggered_event.trigger
+                        );
+                        continue;
+                    }
+
+                    if !control.accept_leadership() {
+                        trace!(
+                            "Standing by, skipping event from Trigger[{}::{}]",
+                            triggered_event.input,
+                            triggered_event.trigger
+                        );
+                        continue;
+                    }
+
                     if let Some(actions) = actions_by_trigger.get(trigger_id) {
                         let send_futures = actions
                             .iter()
-                            .map(|(tx, output_id, action_id)| {
+                            .filter(|(index, _, _, _)| control.accept_handler(*index))
+                            .map(|(_, tx, output_id, action_id)| {
                                 trace!(
                                     "Found Action[{}::{}] for event from Trigger[{}::{}]",
                                     output_id,
@@ -111,6 +148,7 @@ impl ChannelDispatcher {
                                     action: action_id.clone(),
                                     data: triggered_event.to_owned().data,
                                 };
+                                control.count_action(output_id, action_id);
                                 (tx, actionable_event)
                             })
                             .map(|(tx, actionable_event)| tx.send(actionable_event));
@@ -1,27 +1,77 @@
-use crate::common::data::ElId;
+use crate::common::data::{ConnectionId, ElId};
+use crate::common::mqtt::{MqttConnection, MqttConnectionConfig};
 use crate::config::input::InputConfig;
+use crate::config::leader::LeaderElectionConfig;
 use crate::config::output::OutputConfig;
 use crate::config::Config;
-use crate::coordinator::ChannelDispatcher;
+use crate::coordinator::{
+    ChannelDispatcher, DispatcherControl, InternalBus, LeaderElection, Leadership, Lifecycle,
+};
+use crate::health::{ComponentChange, ComponentKind, HealthRegistry};
+use crate::inputs::amqp::AmqpInput;
+use crate::inputs::file::FileInput;
+use crate::inputs::http::HttpInput;
+use crate::inputs::internal::InternalInput;
+#[cfg(feature = "kafka")]
+use crate::inputs::kafka::KafkaInput;
+use crate::inputs::lifecycle::LifecycleInput;
 use crate::inputs::mqtt::MqttInput;
+use crate::inputs::redis::RedisInput;
+use crate::inputs::schedule::ScheduleInput;
+use crate::inputs::socket::{UdpInput, UnixSocketInput};
+use crate::inputs::websocket::WebSocketInput;
 use crate::inputs::InputTask;
+use crate::outputs::amqp::AmqpOutput;
+use crate::outputs::exec::ExecOutput;
+use crate::outputs::file::FileOutput;
+use crate::outputs::http::HttpOutput;
+use crate::outputs::internal::InternalOutput;
+#[cfg(feature = "kafka")]
+use crate::outputs::kafka::KafkaOutput;
 use crate::outputs::mqtt::MqttOutput;
+use crate::outputs::redis::RedisOutput;
+use crate::outputs::sqlite::SqliteOutput;
+use crate::outputs::stdout::StdoutOutput;
+use crate::outputs::websocket::WebSocketOutput;
 use crate::outputs::OutputTask;
 use log::trace;
+use std::collections::HashMap;
 use std::default::Default;
+use tokio::sync::broadcast;
 
 #[derive(Debug, Default)]
 pub struct ChannelManager {}
 
 impl ChannelManager {
-    pub async fn run(config: Config) {
+    pub async fn run(
+        config: Config,
+        health: HealthRegistry,
+        lifecycle: Lifecycle,
+    ) -> DispatcherControl {
         let mut dispatcher = ChannelDispatcher::new();
+        let bus = InternalBus::new();
+        let connections: HashMap<ConnectionId, MqttConnection> = config
+            .connections
+            .iter()
+            .map(|(id, connection_config)| {
+                let connection =
+                    MqttConnection::new(format!("connection-{}", id), connection_config.clone());
+                (id.clone(), connection)
+            })
+            .collect();
+        // Lifecycle inputs subscribe before anything is spawned, not to miss early connections
+        let mut health_changes: HashMap<ElId, broadcast::Receiver<ComponentChange>> = config
+            .inputs
+            .iter()
+            .filter(|(_, input_config)| matches!(input_config, InputConfig::Lifecycle(_)))
+            .map(|(id, _)| (id.clone(), health.subscribe()))
+            .collect();
 
         {
             // spawn outputs
-            for (id, config) in config.outputs.into_iter() {
-                let task = Self::config_to_output(&id, &config);
-                let rx = dispatcher.create_channel_for_output(&id);
+            for (id, output_config) in config.outputs.iter() {
+                let task = Self::config_to_output(id, output_config, &health, &bus, &connections);
+                let rx = dispatcher.create_channel_for_output(id);
 
                 trace!("Spawning {}", task);
                 tokio::spawn(async move {
@@ -32,9 +82,17 @@ impl ChannelManager {
 
         {
             // spawn inputs
-            for (id, config) in config.inputs.into_iter() {
-                let task = Self::config_to_input(&id, &config);
-                let tx = dispatcher.create_channel_for_input(&id);
+            for (id, input_config) in config.inputs.iter() {
+                let task = Self::config_to_input(
+                    id,
+                    input_config,
+                    &health,
+                    &bus,
+                    &lifecycle,
+                    &mut health_changes,
+                    &connections,
+                );
+                let tx = dispatcher.create_channel_for_input(id);
 
                 trace!("Spawning {}", task);
                 tokio::spawn(async move {
@@ -43,26 +101,220 @@ impl ChannelManager {
             }
         }
 
+        let leadership = match &config.leader_election {
+            Some(election_config) => {
+                let leadership = Leadership::elected();
+                let election = Self::config_to_leader_election(
+                    election_config,
+                    &config.connections,
+                    &health,
+                    leadership.clone(),
+                );
+
+                trace!("Spawning {}", election);
+                tokio::spawn(async move {
+                    election.run().await;
+                });
+                leadership
+            }
+            None => Leadership::always(),
+        };
+
+        let control = dispatcher.control(&config, leadership);
+
         {
             // spawn relations
             // TODO: no need to spawn here
-            tokio::spawn(async move { dispatcher.run_handlers(config.handlers).await });
+            let control = control.clone();
+            tokio::spawn(async move {
+                dispatcher
+                    .run_handlers(config.handlers, health, control)
+                    .await
+            });
         }
+
+        control
     }
 
-    fn config_to_input(id: &ElId, config: &InputConfig) -> Box<dyn InputTask> {
-        let task = match config {
-            InputConfig::Mqtt(config) => MqttInput::new(id.clone(), config.clone()),
-        };
+    fn config_to_input(
+        id: &ElId,
+        config: &InputConfig,
+        health: &HealthRegistry,
+        bus: &InternalBus,
+        lifecycle: &Lifecycle,
+        health_changes: &mut HashMap<ElId, broadcast::Receiver<ComponentChange>>,
+        connections: &HashMap<ConnectionId, MqttConnection>,
+    ) -> Box<dyn InputTask> {
+        match config {
+            InputConfig::Mqtt(config) => Box::new(MqttInput::new(
+                id.clone(),
+                config.clone(),
+                health.register(ComponentKind::Input, id),
+                Self::mqtt_connection(
+                    format!("input-{}", id),
+                    config.connection.as_ref(),
+                    &config.settings,
+                    connections,
+                ),
+            )),
+            InputConfig::Http(config) => Box::new(HttpInput::new(
+                id.clone(),
+                config.clone(),
+                health.register(ComponentKind::Input, id),
+            )),
+            InputConfig::Schedule(config) => Box::new(ScheduleInput::new(
+                id.clone(),
+                config.clone(),
+                health.register(ComponentKind::Input, id),
+            )),
+            InputConfig::File(config) => Box::new(FileInput::new(
+                id.clone(),
+                config.clone(),
+                health.register(ComponentKind::Input, id),
+            )),
+            InputConfig::Udp(config) => Box::new(UdpInput::new(
+                id.clone(),
+                config.clone(),
+                health.register(ComponentKind::Input, id),
+            )),
+            InputConfig::UnixSocket(config) => Box::new(UnixSocketInput::new(
+                id.clone(),
+                config.clone(),
+                health.register(ComponentKind::Input, id),
+            )),
+            InputConfig::WebSocket(config) => Box::new(WebSocketInput::new(
+                id.clone(),
+                config.clone(),
+                health.register(ComponentKind::Input, id),
+            )),
+            InputConfig::Redis(config) => Box::new(RedisInput::new(
+                id.clone(),
+                config.clone(),
+                health.register(ComponentKind::Input, id),
+            )),
+            InputConfig::Amqp(config) => Box::new(AmqpInput::new(
+                id.clone(),
+                config.clone(),
+                health.register(ComponentKind::Input, id),
+            )),
+            InputConfig::Internal(config) => Box::new(InternalInput::new(
+                id.clone(),
+                config.clone(),
+                health.register(ComponentKind::Input, id),
+                bus.subscribe(),
+            )),
+            InputConfig::Lifecycle(config) => Box::new(LifecycleInput::new(
+                id.clone(),
+                config.clone(),
+                health.register(ComponentKind::Input, id),
+                lifecycle.subscribe(),
+                health_changes
+                    .remove(id)
+                    .unwrap_or_else(|| health.subscribe()),
+            )),
+            #[cfg(feature = "kafka")]
+            InputConfig::Kafka(config) => Box::new(KafkaInput::new(
+                id.clone(),
+                config.clone(),
+                health.register(ComponentKind::Input, id),
+            )),
+        }
+    }
 
-        Box::new(task)
+    fn config_to_output(
+        id: &ElId,
+        config: &OutputConfig,
+        health: &HealthRegistry,
+        bus: &InternalBus,
+        connections: &HashMap<ConnectionId, MqttConnection>,
+    ) -> Box<dyn OutputTask> {
+        match config {
+            OutputConfig::Mqtt(config) => Box::new(MqttOutput::new(
+                id.clone(),
+                config.clone(),
+                health.register(ComponentKind::Output, id),
+                Self::mqtt_connection(
+                    format!("output-{}", id),
+                    config.connection.as_ref(),
+                    &config.settings,
+                    connections,
+                ),
+            )),
+            OutputConfig::WebSocket(config) => Box::new(WebSocketOutput::new(
+                id.clone(),
+                config.clone(),
+                health.register(ComponentKind::Output, id),
+            )),
+            OutputConfig::Redis(config) => Box::new(RedisOutput::new(
+                id.clone(),
+                config.clone(),
+                health.register(ComponentKind::Output, id),
+            )),
+            OutputConfig::Amqp(config) => Box::new(AmqpOutput::new(
+                id.clone(),
+                config.clone(),
+                health.register(ComponentKind::Output, id),
+            )),
+            #[cfg(feature = "kafka")]
+            OutputConfig::Kafka(config) => Box::new(KafkaOutput::new(
+                id.clone(),
+                config.clone(),
+                health.register(ComponentKind::Output, id),
+            )),
+            // Stateless outputs, there is no connection to report; a restart would not fix a
+            // remote HTTP endpoint being down either
+            OutputConfig::Http(config) => Box::new(HttpOutput::new(id.clone(), config.clone())),
+            OutputConfig::Exec(config) => Box::new(ExecOutput::new(id.clone(), config.clone())),
+            OutputConfig::File(config) => Box::new(FileOutput::new(id.clone(), config.clone())),
+            OutputConfig::Stdout(config) => Box::new(StdoutOutput::new(id.clone(), config.clone())),
+            OutputConfig::Sqlite(config) => Box::new(SqliteOutput::new(id.clone(), config.clone())),
+            OutputConfig::Internal(config) => {
+                Box::new(InternalOutput::new(id.clone(), config.clone(), bus.clone()))
+            }
+        }
     }
 
-    fn config_to_output(id: &ElId, config: &OutputConfig) -> Box<dyn OutputTask> {
-        let task = match config {
-            OutputConfig::Mqtt(config) => MqttOutput::new(id.clone(), config.clone()),
+    /// The election gets a client of its own, as the Last Will of the client releases the lock
+    fn config_to_leader_election(
+        config: &LeaderElectionConfig,
+        connections: &HashMap<ConnectionId, MqttConnectionConfig>,
+        health: &HealthRegistry,
+        leadership: Leadership,
+    ) -> LeaderElection {
+        let instance = config
+            .instance
+            .clone()
+            .unwrap_or_else(LeaderElection::default_instance);
+        let settings = match &config.connection {
+            Some(connection_id) => &connections[connection_id],
+            None => &config.settings,
         };
+        let settings = settings
+            .with_keep_alive(Some(config.keep_alive_secs()))
+            .with_last_will(LeaderElection::last_will(config));
+
+        LeaderElection::new(
+            instance.clone(),
+            config.clone(),
+            MqttConnection::new(format!("leader-{}", instance), settings),
+            health.register(
+                ComponentKind::LeaderElection,
+                &ElId::from(instance.as_str()),
+            ),
+            leadership,
+        )
+    }
 
-        Box::new(task)
+    /// Shared connection the input/output refers to (checked on load), or a connection of its own
+    fn mqtt_connection(
+        name: String,
+        connection_id: Option<&ConnectionId>,
+        settings: &MqttConnectionConfig,
+        connections: &HashMap<ConnectionId, MqttConnection>,
+    ) -> MqttConnection {
+        match connection_id {
+            Some(connection_id) => connections[connection_id].clone(),
+            None => MqttConnection::new(name, settings.clone()),
+        }
     }
 }
@@ -0,0 +1,294 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+
+use itertools::Itertools;
+use serde::Serialize;
+use tokio::sync::mpsc;
+
+use crate::common::data::{
+    ActionId, ActionableEvent, InputId, OutputId, TriggerId, TriggeredEvent,
+};
+use crate::common::types::Result;
+use crate::config::handler::HandlerConfig;
+use crate::config::Config;
+use crate::coordinator::{Leadership, LeadershipReport};
+
+#[derive(Debug)]
+struct RouteStats {
+    enabled: AtomicBool,
+    events: AtomicU64,
+}
+
+impl RouteStats {
+    fn new() -> Self {
+        Self {
+            enabled: AtomicBool::new(true),
+            events: AtomicU64::new(0),
+        }
+    }
+
+    fn accept(&self) -> bool {
+        let enabled = self.enabled.load(Ordering::Relaxed);
+        if enabled {
+            self.events.fetch_add(1, Ordering::Relaxed);
+        }
+        enabled
+    }
+}
+
+#[derive(Debug)]
+struct ControlState {
+    triggers: BTreeMap<(InputId, TriggerId), RouteStats>,
+    actions: BTreeMap<(OutputId, ActionId), RouteStats>,
+    handlers: Vec<(HandlerConfig, RouteStats)>,
+}
+
+/// Runtime view of the routing: counters, enable/disable switches and event injection
+#[derive(Debug, Clone)]
+pub struct DispatcherControl {
+    state: Arc<ControlState>,
+    leadership: Leadership,
+    inputs: HashMap<InputId, mpsc::Sender<TriggeredEvent>>,
+    outputs: HashMap<OutputId, mpsc::Sender<ActionableEvent>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TriggerReport {
+    pub input: String,
+    pub trigger: String,
+    pub enabled: bool,
+    pub events: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ActionReport {
+    pub output: String,
+    pub action: String,
+    pub events: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HandlerReport {
+    pub index: usize,
+    #[serde(rename = "on")]
+    pub trigger: TriggerReport,
+    #[serde(rename = "do")]
+    pub action: ActionReport,
+    pub enabled: bool,
+    pub events: u64,
+}
+
+impl DispatcherControl {
+    pub fn new(
+        config: &Config,
+        leadership: Leadership,
+        inputs: HashMap<InputId, mpsc::Sender<TriggeredEvent>>,
+        outputs: HashMap<OutputId, mpsc::Sender<ActionableEvent>>,
+    ) -> Self {
+        let triggers = config
+            .inputs
+            .iter()
+            .flat_map(|(input_id, input)| {
+                input
+                    .trigger_ids()
+                    .into_iter()
+                    .map(move |trigger_id| ((input_id.clone(), trigger_id), RouteStats::new()))
+            })
+            .collect();
+
+        let actions = config
+            .outputs
+            .iter()
+            .flat_map(|(output_id, output)| {
+                output
+                    .action_ids()
+                    .into_iter()
+                    .map(move |action_id| ((output_id.clone(), action_id), RouteStats::new()))
+            })
+            .collect();
+
+        let handlers = config
+            .handlers
+            .iter()
+            .map(|handler| (handler.clone(), RouteStats::new()))
+            .collect();
+
+        Self {
+            state: Arc::new(ControlState {
+                triggers,
+                actions,
+                handlers,
+            }),
+            leadership,
+            inputs,
+            outputs,
+        }
+    }
+
+    /// Counts the event and tells whether the trigger is enabled; unknown triggers are passed as is
+    pub fn accept_trigger(&self, input_id: &InputId, trigger_id: &TriggerId) -> bool {
+        self.state
+            .triggers
+            .get(&(input_id.clone(), trigger_id.clone()))
+            .map(|stats| stats.accept())
+            .unwrap_or(true)
+    }
+
+    /// Counts the event and tells whether the handler is enabled
+    pub fn accept_handler(&self, index: usize) -> bool {
+        self.state
+            .handlers
+            .get(index)
+            .map(|(_, stats)| stats.accept())
+            .unwrap_or(true)
+    }
+
+    /// Tells whether this instance executes actions, counts the events skipped in standby
+    pub fn accept_leadership(&self) -> bool {
+        self.leadership.accept()
+    }
+
+    pub fn count_action(&self, output_id: &OutputId, action_id: &ActionId) {
+        if let Some(stats) = self
+            .state
+            .actions
+            .get(&(output_id.clone(), action_id.clone()))
+        {
+            stats.accept();
+        }
+    }
+
+    pub fn set_trigger_enabled(
+        &self,
+        input_id: &InputId,
+        trigger_id: &TriggerId,
+        enabled: bool,
+    ) -> bool {
+        self.state
+            .triggers
+            .get(&(input_id.clone(), trigger_id.clone()))
+            .map(|stats| stats.enabled.store(enabled, Ordering::Relaxed))
+            .is_some()
+    }
+
+    pub fn set_handler_enabled(&self, index: usize, enabled: bool) -> bool {
+        self.state
+            .handlers
+            .get(index)
+            .map(|(_, stats)| stats.enabled.store(enabled, Ordering::Relaxed))
+            .is_some()
+    }
+
+    pub fn has_trigger(&self, input_id: &InputId, trigger_id: &TriggerId) -> bool {
+        self.state
+            .triggers
+            .contains_key(&(input_id.clone(), trigger_id.clone()))
+    }
+
+    pub fn has_action(&self, output_id: &OutputId, action_id: &ActionId) -> bool {
+        self.state
+            .actions
+            .contains_key(&(output_id.clone(), action_id.clone()))
+    }
+
+    /// Sends the event to the dispatcher as if it was produced by the input itself
+    pub async fn inject_triggered(&self, event: TriggeredEvent) -> Result<()> {
+        let tx = self
+            .inputs
+            .get(&event.input)
+            .ok_or_else(|| format!("Can not find input with id={}", &event.input))?;
+        tx.send(event).await?;
+        Ok(())
+    }
+
+    /// Sends the event directly to the output, bypassing triggers and handlers
+    pub async fn inject_actionable(&self, event: ActionableEvent) -> Result<()> {
+        let tx = self
+            .outputs
+            .get(&event.output)
+            .ok_or_else(|| format!("Can not find output with id={}", &event.output))?;
+        self.count_action(&event.output, &event.action);
+        tx.send(event).await?;
+        Ok(())
+    }
+
+    pub fn leadership(&self) -> LeadershipReport {
+        self.leadership.report()
+    }
+
+    pub fn input_ids(&self) -> Vec<InputId> {
+        self.inputs.keys().cloned().sorted().collect()
+    }
+
+    pub fn output_ids(&self) -> Vec<OutputId> {
+        self.outputs.keys().cloned().sorted().collect()
+    }
+
+    pub fn triggers(&self) -> Vec<TriggerReport> {
+        self.state
+            .triggers
+            .iter()
+            .map(|((input_id, trigger_id), stats)| TriggerReport {
+                input: input_id.to_string(),
+                trigger: trigger_id.to_string(),
+                enabled: stats.enabled.load(Ordering::Relaxed),
+                events: stats.events.load(Ordering::Relaxed),
+            })
+            .collect()
+    }
+
+    pub fn actions(&self) -> Vec<ActionReport> {
+        self.state
+            .actions
+            .iter()
+            .map(|((output_id, action_id), stats)| ActionReport {
+                output: output_id.to_string(),
+                action: action_id.to_string(),
+                events: stats.events.load(Ordering::Relaxed),
+            })
+            .collect()
+    }
+
+    pub fn handlers(&self) -> Vec<HandlerReport> {
+        let triggers = self.triggers();
+        let actions = self.actions();
+
+        self.state
+            .handlers
+            .iter()
+            .enumerate()
+            .map(|(index, (handler, stats))| {
+                let input_id = handler.trigger.input_id.to_string();
+                let trigger_id = handler.trigger.trigger_id.to_string();
+                let output_id = handler.action.output_id.to_string();
+                let action_id = handler.action.action_id.to_string();
+
+                HandlerReport {
+                    index,
+                    trigger: triggers
+                        .iter()
+                        .find(|x| x.input == input_id && x.trigger == trigger_id)
+                        .cloned()
+                        .unwrap_or(TriggerReport {
+                            input: input_id,
+                            trigger: trigger_id,
+                            enabled: false,
+                            events: 0,
+                        }),
+                    action: actions
+                        .iter()
+                        .find(|x| x.output == output_id && x.action == action_id)
+                        .cloned()
+                        .unwrap_or(ActionReport {
+                            output: output_id,
+                            action: action_id,
+                            events: 0,
+                        }),
+                    enabled: stats.enabled.load(Ordering::Relaxed),
+                    events: stats.events.load(Ordering::Relaxed),
+                }
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,245 @@
+use std::fmt::{Display, Formatter};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use log::{info, trace, warn};
+use paho_mqtt::Message;
+use serde::Serialize;
+use tokio::time::Instant;
+
+use crate::common::mqtt::{MqttConnection, MqttStatusMessageConfig};
+use crate::common::utils::{hostname, random_alphanumeric};
+use crate::config::leader::LeaderElectionConfig;
+use crate::health::ComponentHealth;
+
+#[derive(Debug)]
+struct LeadershipState {
+    election: bool,
+    leader: AtomicBool,
+    /// Instance holding the lock, as last seen
+    holder: RwLock<Option<String>>,
+    changes: AtomicU64,
+    skipped: AtomicU64,
+}
+
+/// Whether this instance executes actions, always the case without a leader election
+#[derive(Debug, Clone)]
+pub struct Leadership {
+    state: Arc<LeadershipState>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LeadershipReport {
+    pub election: bool,
+    pub leader: bool,
+    pub holder: Option<String>,
+    /// How many times leadership was gained or lost
+    pub changes: u64,
+    /// Events not dispatched to actions while in standby
+    pub skipped_events: u64,
+}
+
+impl Leadership {
+    /// Leader for good, when there is no election
+    pub fn always() -> Self {
+        Self::with_election(false)
+    }
+
+    /// Standby until elected
+    pub fn elected() -> Self {
+        Self::with_election(true)
+    }
+
+    fn with_election(election: bool) -> Self {
+        Self {
+            state: Arc::new(LeadershipState {
+                election,
+                leader: AtomicBool::new(!election),
+                holder: RwLock::new(None),
+                changes: AtomicU64::new(0),
+                skipped: AtomicU64::new(0),
+            }),
+        }
+    }
+
+    pub fn is_leader(&self) -> bool {
+        self.state.leader.load(Ordering::Relaxed)
+    }
+
+    /// Tells whether events should be dispatched to actions, counts the skipped ones
+    pub fn accept(&self) -> bool {
+        let leader = self.is_leader();
+        if !leader {
+            self.state.skipped.fetch_add(1, Ordering::Relaxed);
+        }
+        leader
+    }
+
+    /// Returns true if the state changed
+    fn set_leader(&self, leader: bool) -> bool {
+        let changed = self.state.leader.swap(leader, Ordering::Relaxed) != leader;
+        if changed {
+            self.state.changes.fetch_add(1, Ordering::Relaxed);
+        }
+        changed
+    }
+
+    fn set_holder(&self, holder: Option<String>) {
+        *self.state.holder.write().unwrap() = holder;
+    }
+
+    pub fn report(&self) -> LeadershipReport {
+        LeadershipReport {
+            election: self.state.election,
+            leader: self.is_leader(),
+            holder: self.state.holder.read().unwrap().clone(),
+            changes: self.state.changes.load(Ordering::Relaxed),
+            skipped_events: self.state.skipped.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl Default for Leadership {
+    fn default() -> Self {
+        Self::always()
+    }
+}
+
+/// Leader election over MQTT: the leader keeps its id retained in the lock topic, and its Last Will
+/// clears the lock, so a standby claims it once the leader is gone
+#[derive(Debug)]
+pub struct LeaderElection {
+    instance: String,
+    config: LeaderElectionConfig,
+    connection: MqttConnection,
+    health: ComponentHealth,
+    leadership: Leadership,
+}
+
+impl LeaderElection {
+    /// `connection` must be dedicated to the election, it is created with `last_will` below
+    pub fn new(
+        instance: String,
+        config: LeaderElectionConfig,
+        connection: MqttConnection,
+        health: ComponentHealth,
+        leadership: Leadership,
+    ) -> Self {
+        Self {
+            instance,
+            config,
+            connection,
+            health,
+            leadership,
+        }
+    }
+
+    /// Host name, or a random id if it is not known
+    pub fn default_instance() -> String {
+        hostname().unwrap_or_else(|| format!("mqrt-{}", random_alphanumeric()))
+    }
+
+    /// Clears the retained lock when the client is gone
+    pub fn last_will(config: &LeaderElectionConfig) -> MqttStatusMessageConfig {
+        MqttStatusMessageConfig::new(config.topic.clone(), String::new(), paho_mqtt::QOS_1, true)
+    }
+
+    pub async fn run(self) {
+        let mut messages = self
+            .connection
+            .subscribe(vec![self.config.topic.clone()], self.health.clone());
+        let mut connected = self.connection.connected();
+        self.connection.start();
+
+        info!("{} is standby until elected", self);
+
+        // Claims the lock when it stays free, armed until a holder is known
+        let takeover = tokio::time::sleep(Duration::from_secs(0));
+        tokio::pin!(takeover);
+        let mut armed = false;
+
+        loop {
+            tokio::select! {
+                changed = connected.changed() => {
+                    if changed.is_err() {
+                        break;
+                    }
+                    if *connected.borrow() {
+                        // the retained lock, if any, is delivered on subscribe
+                        takeover.as_mut().reset(Instant::now() + self.config.takeover());
+                        armed = true;
+                    } else {
+                        armed = false;
+                        self.set_leader(false, None);
+                    }
+                }
+                received = messages.recv() => match received {
+                    Some(message) => {
+                        armed = false;
+                        match self.holder(&message) {
+                            None if self.leadership.is_leader() => {
+                                warn!("{} lock was cleared, claiming it back", self);
+                                self.claim().await;
+                            }
+                            None => {
+                                trace!("{} lock is free", self);
+                                self.leadership.set_holder(None);
+                                takeover.as_mut().reset(Instant::now() + self.config.takeover());
+                                armed = true;
+                            }
+                            Some(holder) => {
+                                let leader = holder == self.instance;
+                                self.set_leader(leader, Some(holder));
+                            }
+                        }
+                    }
+                    None => break,
+                },
+                _ = &mut takeover, if armed => {
+                    armed = false;
+                    self.claim().await;
+                }
+            }
+        }
+    }
+
+    fn holder(&self, message: &Message) -> Option<String> {
+        Some(message.payload_str().trim().to_string()).filter(|x| !x.is_empty())
+    }
+
+    /// Leadership is taken once the claim comes back from the broker, the last claim wins
+    async fn claim(&self) {
+        info!("{} claims the leadership", self);
+        let message = Message::new_retained(
+            self.config.topic.as_str(),
+            self.instance.as_str(),
+            paho_mqtt::QOS_1,
+        );
+        self.connection
+            .publish(message)
+            .await
+            .unwrap_or_else(|err| warn!("{} can not claim the lock: {:?}", self, err));
+    }
+
+    fn set_leader(&self, leader: bool, holder: Option<String>) {
+        let changed = self.leadership.set_leader(leader);
+        self.leadership.set_holder(holder.clone());
+
+        match (changed, leader) {
+            (true, true) => info!("{} is now the leader", self),
+            (true, false) => warn!(
+                "{} is now standby, the leader is {}",
+                self,
+                holder.as_deref().unwrap_or("unknown")
+            ),
+            _ => {}
+        }
+    }
+}
+
+impl Display for LeaderElection {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "LeaderElection[{}@{}]", self.instance, self.config.topic)
+    }
+}
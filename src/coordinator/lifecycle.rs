@@ -0,0 +1,58 @@
+use std::fmt::{Display, Formatter};
+
+use log::trace;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LifecycleEvent {
+    Startup,
+    Shutdown,
+    InputConnected,
+    InputDisconnected,
+    ConfigReloaded,
+}
+
+impl Display for LifecycleEvent {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LifecycleEvent::Startup => write!(f, "startup"),
+            LifecycleEvent::Shutdown => write!(f, "shutdown"),
+            LifecycleEvent::InputConnected => write!(f, "input_connected"),
+            LifecycleEvent::InputDisconnected => write!(f, "input_disconnected"),
+            LifecycleEvent::ConfigReloaded => write!(f, "config_reloaded"),
+        }
+    }
+}
+
+/// Startup, shutdown and reload events of the application, consumed by `lifecycle` inputs.
+/// Input connection changes come from the `HealthRegistry` instead.
+#[derive(Debug, Clone)]
+pub struct Lifecycle {
+    sender: broadcast::Sender<LifecycleEvent>,
+}
+
+impl Lifecycle {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(16);
+        Self { sender }
+    }
+
+    /// Returns how many inputs received the event
+    pub fn emit(&self, event: LifecycleEvent) -> usize {
+        trace!("Lifecycle event {}", event);
+        self.sender.send(event).unwrap_or(0)
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<LifecycleEvent> {
+        self.sender.subscribe()
+    }
+}
+
+impl Default for Lifecycle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
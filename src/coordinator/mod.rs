@@ -1,5 +1,13 @@
+mod bus;
 mod channel_dispatcher;
 mod channel_manager;
+mod control;
+mod leader;
+mod lifecycle;
 
+pub use bus::{BusEvent, InternalBus};
 pub use channel_dispatcher::ChannelDispatcher;
 pub use channel_manager::ChannelManager;
+pub use control::*;
+pub use leader::{LeaderElection, Leadership, LeadershipReport};
+pub use lifecycle::{Lifecycle, LifecycleEvent};
@@ -0,0 +1,4 @@
+mod registry;
+pub mod watchdog;
+
+pub use registry::*;
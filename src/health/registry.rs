@@ -0,0 +1,195 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::sync::{Arc, RwLock};
+use std::time::Instant;
+
+use serde::Serialize;
+use tokio::sync::broadcast;
+
+use crate::common::data::ElId;
+use crate::config::health::HealthConfig;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ComponentKind {
+    Input,
+    Output,
+    Dispatcher,
+    LeaderElection,
+}
+
+impl Display for ComponentKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ComponentKind::Input => write!(f, "Input"),
+            ComponentKind::Output => write!(f, "Output"),
+            ComponentKind::Dispatcher => write!(f, "Dispatcher"),
+            ComponentKind::LeaderElection => write!(f, "LeaderElection"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ComponentState {
+    connected: bool,
+    since: Instant,
+    heartbeat: Option<Instant>,
+}
+
+impl ComponentState {
+    fn new() -> Self {
+        Self {
+            connected: false,
+            since: Instant::now(),
+            heartbeat: None,
+        }
+    }
+}
+
+type ComponentKey = (ComponentKind, ElId);
+
+/// Sent whenever a component gets connected or disconnected
+#[derive(Debug, Clone)]
+pub struct ComponentChange {
+    pub kind: ComponentKind,
+    pub id: ElId,
+    pub connected: bool,
+}
+
+/// Shared map of connection states of every registered input, output and dispatcher route
+#[derive(Debug, Clone)]
+pub struct HealthRegistry {
+    components: Arc<RwLock<HashMap<ComponentKey, ComponentState>>>,
+    changes: broadcast::Sender<ComponentChange>,
+}
+
+impl HealthRegistry {
+    pub fn new() -> Self {
+        let (changes, _) = broadcast::channel(64);
+        Self {
+            components: Arc::default(),
+            changes,
+        }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<ComponentChange> {
+        self.changes.subscribe()
+    }
+
+    pub fn register(&self, kind: ComponentKind, id: &ElId) -> ComponentHealth {
+        self.components
+            .write()
+            .unwrap()
+            .insert((kind, id.clone()), ComponentState::new());
+
+        ComponentHealth {
+            key: (kind, id.clone()),
+            components: self.components.clone(),
+            changes: self.changes.clone(),
+        }
+    }
+
+    pub fn report(&self, config: &HealthConfig) -> HealthReport {
+        let now = Instant::now();
+
+        let mut components: Vec<ComponentReport> = self
+            .components
+            .read()
+            .unwrap()
+            .iter()
+            .map(|((kind, id), state)| {
+                let (healthy, ready) = match state.heartbeat {
+                    Some(heartbeat) => {
+                        let alive = now.duration_since(heartbeat) < config.stalled_timeout();
+                        (alive, alive)
+                    }
+                    None if state.connected => (true, true),
+                    None => (
+                        now.duration_since(state.since) < config.disconnected_timeout(),
+                        false,
+                    ),
+                };
+
+                ComponentReport {
+                    kind: *kind,
+                    id: id.to_string(),
+                    connected: state.connected,
+                    since_secs: now.duration_since(state.since).as_secs(),
+                    healthy,
+                    ready,
+                }
+            })
+            .collect();
+        components.sort_by(|a, b| (a.kind, &a.id).cmp(&(b.kind, &b.id)));
+
+        HealthReport {
+            healthy: components.iter().all(|x| x.healthy),
+            ready: components.iter().all(|x| x.ready),
+            components,
+        }
+    }
+}
+
+impl Default for HealthRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Handle used by a single component to publish its state into the `HealthRegistry`
+#[derive(Debug, Clone)]
+pub struct ComponentHealth {
+    key: ComponentKey,
+    components: Arc<RwLock<HashMap<ComponentKey, ComponentState>>>,
+    changes: broadcast::Sender<ComponentChange>,
+}
+
+impl ComponentHealth {
+    pub fn set_connected(&self, connected: bool) {
+        if let Some(state) = self.components.write().unwrap().get_mut(&self.key) {
+            if state.connected != connected {
+                state.connected = connected;
+                state.since = Instant::now();
+                self.notify(connected);
+            }
+        }
+    }
+
+    pub fn heartbeat(&self) {
+        if let Some(state) = self.components.write().unwrap().get_mut(&self.key) {
+            let now = Instant::now();
+            if !state.connected {
+                state.connected = true;
+                state.since = now;
+                self.notify(true);
+            }
+            state.heartbeat = Some(now);
+        }
+    }
+
+    fn notify(&self, connected: bool) {
+        // Nobody may be listening, that is fine
+        let _ = self.changes.send(ComponentChange {
+            kind: self.key.0,
+            id: self.key.1.clone(),
+            connected,
+        });
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ComponentReport {
+    pub kind: ComponentKind,
+    pub id: String,
+    pub connected: bool,
+    pub since_secs: u64,
+    pub healthy: bool,
+    pub ready: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthReport {
+    pub healthy: bool,
+    pub ready: bool,
+    pub components: Vec<ComponentReport>,
+}
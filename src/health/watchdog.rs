@@ -0,0 +1,49 @@
+use std::time::Duration;
+
+use log::{debug, warn};
+use sd_notify::NotifyState;
+
+use crate::config::health::HealthConfig;
+use crate::health::HealthRegistry;
+
+pub fn notify_ready() {
+    sd_notify::notify(false, &[NotifyState::Ready])
+        .unwrap_or_else(|err| warn!("Can not notify systemd: {:?}", err));
+}
+
+pub fn notify_reloading() {
+    sd_notify::notify(false, &[NotifyState::Reloading])
+        .unwrap_or_else(|err| warn!("Can not notify systemd: {:?}", err));
+}
+
+/// Pings the systemd watchdog at half of `WatchdogSec` for as long as the registry reports healthy
+pub async fn run(health: HealthRegistry, config: HealthConfig) {
+    let mut usec = 0;
+    if !sd_notify::watchdog_enabled(false, &mut usec) {
+        debug!("Systemd watchdog is not enabled");
+        return;
+    }
+
+    let interval = Duration::from_micros(usec) / 2;
+    debug!("Pinging systemd watchdog every {:?}", interval);
+
+    loop {
+        tokio::time::sleep(interval).await;
+
+        let report = health.report(&config);
+        if report.healthy {
+            sd_notify::notify(false, &[NotifyState::Watchdog])
+                .unwrap_or_else(|err| warn!("Can not ping systemd watchdog: {:?}", err));
+        } else {
+            warn!(
+                "Skipping systemd watchdog ping, unhealthy components: {:?}",
+                report
+                    .components
+                    .iter()
+                    .filter(|x| !x.healthy)
+                    .map(|x| format!("{}[{}]", x.kind, x.id))
+                    .collect::<Vec<_>>()
+            );
+        }
+    }
+}
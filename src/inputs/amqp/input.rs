@@ -0,0 +1,193 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use futures_util::stream::{self, StreamExt};
+use itertools::Itertools;
+use lapin::message::Delivery;
+use lapin::options::{
+    BasicAckOptions, BasicConsumeOptions, BasicNackOptions, BasicQosOptions, QueueDeclareOptions,
+};
+use lapin::types::FieldTable;
+use log::{info, trace, warn};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc::Sender;
+
+use crate::common::amqp::AmqpConnectionConfig;
+use crate::common::data::{InputId, TriggerId, TriggeredEvent};
+use crate::common::types::Result;
+use crate::health::ComponentHealth;
+use crate::inputs::amqp::trigger::{AmqpTrigger, AmqpTriggerConfig};
+use crate::inputs::InputTask;
+
+const RETRY_INTERVAL: Duration = Duration::from_secs(5);
+
+// Config
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct AmqpInputConfig {
+    #[serde(flatten)]
+    pub connection: AmqpConnectionConfig,
+    /// How many unacknowledged messages the broker delivers at once
+    #[serde(default = "default_prefetch")]
+    prefetch: u16,
+    #[serde(rename = "trigger")]
+    #[serde(default)]
+    pub triggers: HashMap<TriggerId, AmqpTriggerConfig>,
+}
+
+fn default_prefetch() -> u16 {
+    10
+}
+
+impl AmqpInputConfig {
+    pub fn validate(&self) -> Result<()> {
+        self.connection.validate()
+    }
+}
+
+#[derive(Debug)]
+pub struct AmqpInput {
+    id: InputId,
+    triggers: Vec<AmqpTrigger>,
+    config: AmqpInputConfig,
+    health: ComponentHealth,
+}
+
+impl AmqpInput {
+    pub fn new(id: InputId, config: AmqpInputConfig, health: ComponentHealth) -> Self {
+        let triggers = config
+            .triggers
+            .clone()
+            .into_iter()
+            .map(|(trigger_id, trigger_config)| {
+                AmqpTrigger::new(id.clone(), trigger_id, trigger_config)
+            })
+            .collect_vec();
+        Self {
+            id,
+            triggers,
+            config,
+            health,
+        }
+    }
+
+    /// Consumes every queue used by the triggers until the connection is lost
+    async fn consume(&self, chan: &Sender<TriggeredEvent>) -> Result<()> {
+        let conn = self
+            .config
+            .connection
+            .connect(format!("mqrt-input-{}", self.id))
+            .await?;
+        let channel = conn.create_channel().await?;
+        channel
+            .basic_qos(self.config.prefetch, BasicQosOptions::default())
+            .await?;
+
+        let queues = self
+            .triggers
+            .iter()
+            .map(|t| t.config.queue.clone())
+            .unique()
+            .collect_vec();
+        let mut consumers = Vec::new();
+        for queue in queues {
+            let declare = self
+                .triggers
+                .iter()
+                .any(|t| t.config.queue == queue && t.config.declare);
+            if declare {
+                let options = QueueDeclareOptions {
+                    durable: true,
+                    ..QueueDeclareOptions::default()
+                };
+                channel
+                    .queue_declare(&queue, options, FieldTable::default())
+                    .await?;
+            }
+
+            let consumer = channel
+                .basic_consume(
+                    &queue,
+                    &format!("mqrt-{}-{}", self.id, queue),
+                    BasicConsumeOptions::default(),
+                    FieldTable::default(),
+                )
+                .await?;
+            consumers.push(
+                consumer
+                    .map(move |delivery| (queue.clone(), delivery))
+                    .boxed(),
+            );
+        }
+
+        info!("{} consuming from {}", self, self.config.connection.host());
+        self.health.set_connected(true);
+
+        let mut deliveries = stream::select_all(consumers);
+        while let Some((queue, delivery)) = deliveries.next().await {
+            self.dispatch(&queue, delivery?, chan).await?;
+        }
+
+        Err("lost the connection".into())
+    }
+
+    /// Acknowledges the message once every matching trigger has passed it on, it is requeued otherwise
+    async fn dispatch(
+        &self,
+        queue: &str,
+        delivery: Delivery,
+        chan: &Sender<TriggeredEvent>,
+    ) -> Result<()> {
+        trace!(
+            "{} received {:?} from {:?} with {:?}",
+            self,
+            delivery.data,
+            queue,
+            delivery.routing_key
+        );
+
+        let mut dispatched = true;
+        for trigger in self.triggers.iter().filter(|t| t.config.queue == queue) {
+            if let Some(triggered_event) = trigger.process(&delivery) {
+                trace!("{} processed the message", trigger);
+                if let Err(err) = chan.send(triggered_event).await {
+                    warn!("Can not send TriggeredEvent {:?}", &err);
+                    dispatched = false;
+                }
+            }
+        }
+
+        if dispatched {
+            delivery.ack(BasicAckOptions::default()).await?;
+        } else {
+            let options = BasicNackOptions {
+                requeue: true,
+                ..BasicNackOptions::default()
+            };
+            delivery.nack(options).await?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for AmqpInput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "AmqpInput[{}]", self.id)
+    }
+}
+
+#[async_trait]
+impl InputTask for AmqpInput {
+    async fn run(self: Box<Self>, chan: Sender<TriggeredEvent>) {
+        loop {
+            if let Err(err) = self.consume(&chan).await {
+                warn!("{} {}, retrying in {:?}", self, err, RETRY_INTERVAL);
+            }
+            self.health.set_connected(false);
+            tokio::time::sleep(RETRY_INTERVAL).await;
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use std::fmt::{Display, Formatter};
+
+use bytes::Bytes;
+use lapin::message::Delivery;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::common::data::{DataEvent, DataEventMeta, InputId, TriggerId, TriggeredEvent};
+use crate::inputs::filter::TriggerFilterConfig;
+
+// Config
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct AmqpTriggerConfig {
+    pub queue: String,
+    /// Declare the queue as durable if it does not exist yet
+    #[serde(default)]
+    pub declare: bool,
+    /// JavaScript filters get the routing key as `topic`
+    #[serde(default)]
+    filter: TriggerFilterConfig,
+}
+
+// Trigger
+#[derive(Debug, Clone)]
+pub struct AmqpTrigger {
+    input_id: InputId,
+    trigger_id: TriggerId,
+    pub config: AmqpTriggerConfig,
+}
+
+impl Display for AmqpTrigger {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "AmqpTrigger[{}::{}]", self.input_id, self.trigger_id)
+    }
+}
+
+impl AmqpTrigger {
+    pub fn new(input_id: InputId, trigger_id: TriggerId, config: AmqpTriggerConfig) -> Self {
+        Self {
+            input_id,
+            trigger_id,
+            config,
+        }
+    }
+
+    pub fn process(&self, delivery: &Delivery) -> Option<TriggeredEvent> {
+        let routing_key = delivery.routing_key.as_str();
+        if !self.config.filter.matches(routing_key, &delivery.data) {
+            return None;
+        }
+
+        Some(TriggeredEvent {
+            input: self.input_id.clone(),
+            trigger: self.trigger_id.clone(),
+            data: DataEvent {
+                payload: Bytes::copy_from_slice(&delivery.data),
+                meta: DataEventMeta::Amqp {
+                    exchange: delivery.exchange.to_string(),
+                    routing_key: routing_key.to_string(),
+                },
+            },
+        })
+    }
+}
@@ -0,0 +1,114 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use log::{error, info, trace, warn};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc::Sender;
+
+use crate::common::data::{InputId, TriggerId, TriggeredEvent};
+use crate::common::types::Result;
+use crate::health::ComponentHealth;
+use crate::inputs::file::tail::Tail;
+use crate::inputs::file::trigger::{FileTrigger, FileTriggerConfig};
+use crate::inputs::InputTask;
+
+const RETRY_INTERVAL: Duration = Duration::from_secs(5);
+
+// Config
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct FileInputConfig {
+    /// Regular file or named pipe
+    path: String,
+    /// Read lines already in the file on start, otherwise only new ones
+    #[serde(default)]
+    from_start: bool,
+    /// How often a regular file is checked for new lines
+    #[serde(default = "default_poll_interval_ms")]
+    poll_interval_ms: u64,
+    #[serde(rename = "trigger")]
+    #[serde(default)]
+    pub triggers: HashMap<TriggerId, FileTriggerConfig>,
+}
+
+fn default_poll_interval_ms() -> u64 {
+    500
+}
+
+impl FileInputConfig {
+    pub fn validate(&self) -> Result<()> {
+        for (trigger_id, trigger) in &self.triggers {
+            trigger
+                .validate()
+                .map_err(|err| format!("Trigger[{}]: {}", trigger_id, err))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct FileInput {
+    id: InputId,
+    config: FileInputConfig,
+    health: ComponentHealth,
+}
+
+impl FileInput {
+    pub fn new(id: InputId, config: FileInputConfig, health: ComponentHealth) -> Self {
+        Self { id, config, health }
+    }
+}
+
+impl Display for FileInput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "FileInput[{}]", self.id)
+    }
+}
+
+#[async_trait]
+impl InputTask for FileInput {
+    async fn run(self: Box<Self>, chan: Sender<TriggeredEvent>) {
+        let mut triggers = Vec::new();
+        for (trigger_id, trigger_config) in self.config.triggers.clone() {
+            match FileTrigger::new(self.id.clone(), trigger_id, trigger_config) {
+                Ok(trigger) => triggers.push(trigger),
+                Err(err) => error!("{}: {}", self, err),
+            }
+        }
+
+        let mut tail = Tail::new(
+            PathBuf::from(&self.config.path),
+            self.config.from_start,
+            Duration::from_millis(self.config.poll_interval_ms),
+            self.health.clone(),
+        );
+        info!("{} reading {}", self, self.config.path);
+
+        loop {
+            let line = match tail.next_line().await {
+                Ok(line) => line,
+                Err(err) => {
+                    warn!("{} {}, retrying in {:?}", self, err, RETRY_INTERVAL);
+                    self.health.set_connected(false);
+                    tokio::time::sleep(RETRY_INTERVAL).await;
+                    continue;
+                }
+            };
+            trace!("{} read {:?}", self, line);
+
+            for trigger in &triggers {
+                if let Some(triggered_event) = trigger.process(&self.config.path, &line) {
+                    trace!("{} processed the line", trigger);
+                    chan.send(triggered_event)
+                        .await
+                        .unwrap_or_else(|err| warn!("Can not send TriggeredEvent {:?}", &err));
+                }
+            }
+        }
+    }
+}
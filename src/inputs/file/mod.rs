@@ -0,0 +1,6 @@
+mod input;
+mod tail;
+mod trigger;
+
+pub use input::FileInput;
+pub use input::FileInputConfig;
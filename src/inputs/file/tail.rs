@@ -0,0 +1,156 @@
+use std::io::SeekFrom;
+use std::os::unix::fs::{FileTypeExt, MetadataExt};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use log::{debug, info};
+use tokio::fs::{self, File};
+use tokio::io::{AsyncBufReadExt, AsyncSeekExt, BufReader};
+
+use crate::common::types::Result;
+use crate::health::ComponentHealth;
+
+/// Reads lines of a growing file like `tail -F`, or of a named pipe. The component is connected
+/// while the path can be opened, whether or not anything is written to it.
+#[derive(Debug)]
+pub struct Tail {
+    path: PathBuf,
+    health: ComponentHealth,
+    from_start: bool,
+    poll_interval: Duration,
+    reader: Option<BufReader<File>>,
+    fifo: bool,
+    inode: u64,
+    /// The path points to a new file, the old one is read to its end before switching to it
+    rotated: bool,
+    position: u64,
+    partial: Vec<u8>,
+}
+
+impl Tail {
+    pub fn new(
+        path: PathBuf,
+        from_start: bool,
+        poll_interval: Duration,
+        health: ComponentHealth,
+    ) -> Self {
+        Self {
+            path,
+            health,
+            from_start,
+            poll_interval,
+            reader: None,
+            fifo: false,
+            inode: 0,
+            rotated: false,
+            position: 0,
+            partial: Vec::new(),
+        }
+    }
+
+    /// Next complete line without the trailing newline, waits for it to be written
+    pub async fn next_line(&mut self) -> Result<String> {
+        loop {
+            if self.reader.is_none() {
+                self.open().await?;
+            }
+            let reader = match self.reader.as_mut() {
+                Some(reader) => reader,
+                None => continue,
+            };
+
+            let read = reader.read_until(b'\n', &mut self.partial).await?;
+            self.position += read as u64;
+
+            if self.partial.last() == Some(&b'\n') {
+                let line = String::from_utf8_lossy(&self.partial)
+                    .trim_end_matches(&['\r', '\n'][..])
+                    .to_string();
+                self.partial.clear();
+                return Ok(line);
+            }
+            if read > 0 {
+                continue;
+            }
+
+            if self.fifo {
+                // The writer has closed the pipe, wait for the next one
+                debug!("{} is closed by the writer", self.path.display());
+                self.reader = None;
+                continue;
+            }
+
+            if self.rotated {
+                debug!("{} is read to the end, reopening", self.path.display());
+                self.reader = None;
+                // The last line of the old file may have no newline
+                if !self.partial.is_empty() {
+                    let line = String::from_utf8_lossy(&self.partial)
+                        .trim_end_matches('\r')
+                        .to_string();
+                    self.partial.clear();
+                    return Ok(line);
+                }
+                continue;
+            }
+
+            tokio::time::sleep(self.poll_interval).await;
+            self.check_rotation().await?;
+        }
+    }
+
+    async fn open(&mut self) -> Result<()> {
+        fs::metadata(&self.path)
+            .await
+            .map_err(|err| format!("Can not open {}: {}", self.path.display(), err))?;
+        // Opening a named pipe waits for a writer, having none is fine
+        self.health.set_connected(true);
+
+        let file = File::open(&self.path)
+            .await
+            .map_err(|err| format!("Can not open {}: {}", self.path.display(), err))?;
+        let metadata = file.metadata().await?;
+
+        self.fifo = metadata.file_type().is_fifo();
+        self.inode = metadata.ino();
+        self.rotated = false;
+        self.partial.clear();
+
+        let mut reader = BufReader::new(file);
+        self.position = match self.from_start || self.fifo {
+            true => 0,
+            false => reader.seek(SeekFrom::End(0)).await?,
+        };
+        // Files appearing later, e.g. after rotation, are read from the start
+        self.from_start = true;
+
+        self.reader = Some(reader);
+
+        Ok(())
+    }
+
+    async fn check_rotation(&mut self) -> Result<()> {
+        let metadata = match fs::metadata(&self.path).await {
+            Ok(metadata) => metadata,
+            // Rotated away and not created yet, keep the old file until it is
+            Err(_) => return Ok(()),
+        };
+
+        if metadata.ino() != self.inode {
+            info!("{} is rotated, reopening", self.path.display());
+            self.rotated = true;
+        } else if metadata.len() < self.position {
+            info!(
+                "{} is truncated, reading from the start",
+                self.path.display()
+            );
+            if let Some(reader) = self.reader.as_mut() {
+                reader.seek(SeekFrom::Start(0)).await?;
+            }
+            self.position = 0;
+            self.partial.clear();
+        }
+
+        Ok(())
+    }
+}
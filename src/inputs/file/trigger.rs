@@ -0,0 +1,122 @@
+use std::fmt::{Display, Formatter};
+
+use bytes::Bytes;
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::common::data::{DataEvent, DataEventMeta, InputId, TriggerId, TriggeredEvent};
+use crate::common::types::Result;
+
+// Config
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct FileTriggerConfig {
+    /// Regular expression, every line matches if not set
+    pattern: Option<String>,
+    #[serde(default)]
+    payload: FileTriggerPayloadConfig,
+}
+
+impl FileTriggerConfig {
+    pub fn validate(&self) -> Result<()> {
+        self.regex().map(|_| ())
+    }
+
+    fn regex(&self) -> Result<Option<Regex>> {
+        match (&self.pattern, &self.payload) {
+            (None, FileTriggerPayloadConfig::Captures) => {
+                Err("`captures` payload needs a `pattern`".into())
+            }
+            (None, _) => Ok(None),
+            (Some(pattern), _) => Regex::new(pattern)
+                .map(Some)
+                .map_err(|err| format!("Invalid pattern {:?}: {}", pattern, err).into()),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum FileTriggerPayloadConfig {
+    /// The line without the trailing newline
+    Line,
+    /// JSON object with capture groups of the pattern, by name or by index for unnamed ones
+    Captures,
+}
+
+impl Default for FileTriggerPayloadConfig {
+    fn default() -> Self {
+        FileTriggerPayloadConfig::Line
+    }
+}
+
+// Trigger
+#[derive(Debug, Clone)]
+pub struct FileTrigger {
+    input_id: InputId,
+    trigger_id: TriggerId,
+    config: FileTriggerConfig,
+    regex: Option<Regex>,
+}
+
+impl Display for FileTrigger {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "FileTrigger[{}::{}]", self.input_id, self.trigger_id)
+    }
+}
+
+impl FileTrigger {
+    pub fn new(
+        input_id: InputId,
+        trigger_id: TriggerId,
+        config: FileTriggerConfig,
+    ) -> Result<Self> {
+        let regex = config.regex()?;
+        Ok(Self {
+            input_id,
+            trigger_id,
+            config,
+            regex,
+        })
+    }
+
+    pub fn process(&self, path: &str, line: &str) -> Option<TriggeredEvent> {
+        let payload = match (&self.regex, &self.config.payload) {
+            (None, _) => Bytes::from(line.to_string()),
+            (Some(regex), FileTriggerPayloadConfig::Line) => {
+                if !regex.is_match(line) {
+                    return None;
+                }
+                Bytes::from(line.to_string())
+            }
+            (Some(regex), FileTriggerPayloadConfig::Captures) => {
+                let captures = regex.captures(line)?;
+                let groups = regex
+                    .capture_names()
+                    .enumerate()
+                    .skip(1)
+                    .filter_map(|(index, name)| {
+                        let key = name.map(String::from).unwrap_or_else(|| index.to_string());
+                        captures
+                            .get(index)
+                            .map(|value| (key, Value::String(value.as_str().to_string())))
+                    })
+                    .collect::<Map<String, Value>>();
+                Bytes::from(Value::Object(groups).to_string())
+            }
+        };
+
+        Some(TriggeredEvent {
+            input: self.input_id.clone(),
+            trigger: self.trigger_id.clone(),
+            data: DataEvent {
+                payload,
+                meta: DataEventMeta::File {
+                    path: path.to_string(),
+                },
+            },
+        })
+    }
+}
@@ -0,0 +1,117 @@
+use std::str::FromStr;
+
+use log::{error, warn};
+use rquickjs as rjs;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::common::types::Result;
+
+/// Payload filter of a trigger, shared by the inputs
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TriggerFilterConfig {
+    NoFilter,
+    DropAll,
+    /// JavaScript body getting `topic` (the source of the message, e.g. a peer address) and `payload`
+    Js {
+        code: String,
+    },
+    Json {
+        field: String,
+        exact: String,
+    },
+}
+
+impl Default for TriggerFilterConfig {
+    fn default() -> Self {
+        TriggerFilterConfig::NoFilter
+    }
+}
+
+impl TriggerFilterConfig {
+    pub fn matches(&self, topic: &str, payload: &[u8]) -> bool {
+        match self {
+            TriggerFilterConfig::NoFilter => true,
+            TriggerFilterConfig::DropAll => false,
+            TriggerFilterConfig::Js { code } => process_js(code, topic, payload.to_vec())
+                .unwrap_or_else(|err| {
+                    error!("Can not process javascript filter code={}: {:?}", code, err);
+                    false
+                }),
+            TriggerFilterConfig::Json { field, exact } => {
+                process_json(field, exact, topic, payload.to_vec()).unwrap_or_else(|err| {
+                    error!(
+                        "Can not process json filter field={:?}, exact={:?}: {:?}",
+                        field, exact, err
+                    );
+                    false
+                })
+            }
+        }
+    }
+}
+
+fn process_js(code: &str, topic: &str, payload: Vec<u8>) -> Result<bool> {
+    let rt = rjs::Runtime::new().unwrap();
+    let ctx = rjs::Context::full(&rt).unwrap();
+
+    let result: Result<bool> = ctx.with(|ctx| {
+        let func: rjs::Function = ctx.eval(format!(
+            "(topic, payload) => {{ return !!(() => {{ {} }})() }}",
+            code
+        ))?;
+
+        let topic_string = topic;
+        let payload_string = String::from_utf8(payload)?;
+
+        let result = func.call((topic_string, payload_string))?;
+        Ok(result)
+    });
+
+    result
+}
+
+fn process_json(field: &str, exact: &str, _topic: &str, payload: Vec<u8>) -> Result<bool> {
+    let payload_str = String::from_utf8(payload)?;
+
+    let json_value = serde_json::from_str(payload_str.as_str())?;
+
+    let result = match json_value {
+        Value::Null => {
+            if !field.is_empty() {
+                warn!("Field is not empty, but the value is Boolean");
+            }
+            Ok(exact.to_lowercase().eq("null"))
+        }
+        Value::Bool(value) => {
+            if !field.is_empty() {
+                warn!("Field is not empty, but the value is Boolean");
+            }
+            Ok(exact.to_lowercase().eq(value.to_string().as_str()))
+        }
+        Value::Number(value) => {
+            if !field.is_empty() {
+                warn!("Field is not empty, but the value is Number");
+            }
+            Ok(value.eq(&serde_json::Number::from_str(exact)?))
+        }
+        Value::String(value) => {
+            if !field.is_empty() {
+                warn!("Field is not empty, but the value is String");
+            }
+            Ok(value.eq(exact))
+        }
+        Value::Array(value) => value
+            .get(field.parse::<usize>()?)
+            .map(|value| value.eq(exact))
+            .ok_or_else(|| String::from("Invalid array field key")),
+        Value::Object(value) => value
+            .get(field)
+            .map(|value| value.eq(exact))
+            .ok_or_else(|| String::from("Invalid object field key")),
+    };
+
+    Ok(result?)
+}
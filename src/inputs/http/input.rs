@@ -0,0 +1,250 @@
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::fmt::{Display, Formatter};
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use bytes::{Bytes, BytesMut};
+use hyper::body::HttpBody;
+use hyper::header::HeaderName;
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{header, Body, Request, Response, Server, StatusCode};
+use itertools::Itertools;
+use log::{error, info, trace, warn};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc::Sender;
+
+use crate::common::data::{InputId, TriggerId, TriggeredEvent};
+use crate::common::secret::Secret;
+use crate::health::ComponentHealth;
+use crate::inputs::http::trigger::{HttpRequest, HttpTrigger, HttpTriggerConfig};
+use crate::inputs::InputTask;
+
+// Config
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct HttpInputConfig {
+    listen: SocketAddr,
+    /// Require `Authorization: Bearer <token>` on every request
+    token: Option<Secret>,
+    /// Larger requests are rejected with 413
+    #[serde(default = "default_max_body_bytes")]
+    max_body_bytes: usize,
+    #[serde(rename = "trigger")]
+    #[serde(default)]
+    pub triggers: HashMap<TriggerId, HttpTriggerConfig>,
+}
+
+fn default_max_body_bytes() -> usize {
+    1024 * 1024
+}
+
+/// Credentials are not passed into events, as those get logged and published
+const SENSITIVE_HEADERS: [HeaderName; 3] = [
+    header::AUTHORIZATION,
+    header::PROXY_AUTHORIZATION,
+    header::COOKIE,
+];
+
+#[derive(Debug)]
+pub struct HttpInput {
+    id: InputId,
+    triggers: Vec<HttpTrigger>,
+    config: HttpInputConfig,
+    health: ComponentHealth,
+}
+
+impl HttpInput {
+    pub fn new(id: InputId, config: HttpInputConfig, health: ComponentHealth) -> Self {
+        let triggers = config
+            .triggers
+            .clone()
+            .into_iter()
+            .map(|(trigger_id, trigger_config)| {
+                HttpTrigger::new(id.clone(), trigger_id, trigger_config)
+            })
+            .collect_vec();
+        Self {
+            id,
+            triggers,
+            config,
+            health,
+        }
+    }
+}
+
+impl Display for HttpInput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "HttpInput[{}]", self.id)
+    }
+}
+
+#[async_trait]
+impl InputTask for HttpInput {
+    async fn run(self: Box<Self>, chan: Sender<TriggeredEvent>) {
+        let name = self.to_string();
+        let listen = self.config.listen;
+        let token = self.config.token.clone();
+        let max_body_bytes = self.config.max_body_bytes;
+        let triggers = Arc::new(self.triggers);
+
+        let make_service = make_service_fn(move |_conn| {
+            let triggers = triggers.clone();
+            let token = token.clone();
+            let chan = chan.clone();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |request| {
+                    let triggers = triggers.clone();
+                    let token = token.clone();
+                    let chan = chan.clone();
+                    async move {
+                        Ok::<_, Infallible>(
+                            process_request(
+                                &triggers,
+                                token.as_ref(),
+                                max_body_bytes,
+                                chan,
+                                request,
+                            )
+                            .await,
+                        )
+                    }
+                }))
+            }
+        });
+
+        let server = match Server::try_bind(&listen) {
+            Ok(builder) => builder.serve(make_service),
+            Err(err) => {
+                error!("{} can not bind to {}: {:?}", name, listen, err);
+                return;
+            }
+        };
+
+        info!("{} listening on http://{}", name, listen);
+        self.health.set_connected(true);
+
+        if let Err(err) = server.await {
+            error!("{} failed: {:?}", name, err);
+        }
+        self.health.set_connected(false);
+    }
+}
+
+async fn process_request(
+    triggers: &[HttpTrigger],
+    token: Option<&Secret>,
+    max_body_bytes: usize,
+    chan: Sender<TriggeredEvent>,
+    request: Request<Body>,
+) -> Response<Body> {
+    trace!("HttpInput received {} {}", request.method(), request.uri());
+
+    if let Some(token) = token {
+        let expected = format!("Bearer {}", token.expose());
+        let authorization = request
+            .headers()
+            .get(header::AUTHORIZATION)
+            .map(|value| value.as_bytes());
+        if authorization != Some(expected.as_bytes()) {
+            return empty_response(StatusCode::UNAUTHORIZED);
+        }
+    }
+
+    let path = request.uri().path().to_string();
+    let path_triggers = triggers
+        .iter()
+        .filter(|trigger| trigger.matches_path(&path))
+        .collect_vec();
+    if path_triggers.is_empty() {
+        return empty_response(StatusCode::NOT_FOUND);
+    }
+    if !path_triggers
+        .iter()
+        .any(|trigger| trigger.matches_method(request.method()))
+    {
+        return empty_response(StatusCode::METHOD_NOT_ALLOWED);
+    }
+
+    let (parts, body) = request.into_parts();
+    let body = match read_body(body, max_body_bytes).await {
+        Ok(Some(body)) => body,
+        Ok(None) => {
+            warn!("Request body is larger than {} bytes", max_body_bytes);
+            return empty_response(StatusCode::PAYLOAD_TOO_LARGE);
+        }
+        Err(err) => {
+            warn!("Can not read request body: {:?}", err);
+            return empty_response(StatusCode::BAD_REQUEST);
+        }
+    };
+
+    let http_request = HttpRequest {
+        method: parts.method,
+        path,
+        query: parts
+            .uri
+            .query()
+            .map(|query| {
+                form_urlencoded::parse(query.as_bytes())
+                    .into_owned()
+                    .collect()
+            })
+            .unwrap_or_default(),
+        headers: parts
+            .headers
+            .iter()
+            .filter(|(name, _)| !SENSITIVE_HEADERS.contains(name))
+            .filter_map(|(name, value)| {
+                value
+                    .to_str()
+                    .ok()
+                    .map(|value| (name.to_string(), value.to_string()))
+            })
+            .collect(),
+        body,
+    };
+
+    for trigger in path_triggers {
+        if let Some(triggered_event) = trigger.process(&http_request) {
+            trace!(
+                "{} processed request {} {}",
+                trigger,
+                http_request.method,
+                http_request.path
+            );
+            chan.send(triggered_event)
+                .await
+                .unwrap_or_else(|err| warn!("Can not send TriggeredEvent {:?}", &err));
+        }
+    }
+
+    empty_response(StatusCode::ACCEPTED)
+}
+
+/// `None` if the body is larger than the limit
+async fn read_body(mut body: Body, limit: usize) -> hyper::Result<Option<Bytes>> {
+    if body.size_hint().lower() > limit as u64 {
+        return Ok(None);
+    }
+
+    let mut data = BytesMut::new();
+    while let Some(chunk) = body.data().await {
+        let chunk = chunk?;
+        if data.len() + chunk.len() > limit {
+            return Ok(None);
+        }
+        data.extend_from_slice(&chunk);
+    }
+
+    Ok(Some(data.freeze()))
+}
+
+fn empty_response(status: StatusCode) -> Response<Body> {
+    Response::builder()
+        .status(status)
+        .body(Body::empty())
+        .unwrap()
+}
@@ -0,0 +1,5 @@
+mod input;
+mod trigger;
+
+pub use input::HttpInput;
+pub use input::HttpInputConfig;
@@ -0,0 +1,129 @@
+use bytes::Bytes;
+use hyper::Method;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::fmt::{Display, Formatter};
+
+use crate::common::data::{DataEvent, DataEventMeta, InputId, TriggerId, TriggeredEvent};
+
+// Config
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct HttpTriggerConfig {
+    #[serde(default = "default_method")]
+    pub method: String,
+    pub path: String,
+    #[serde(default)]
+    payload: HttpTriggerPayloadConfig,
+}
+
+fn default_method() -> String {
+    String::from("POST")
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum HttpTriggerPayloadConfig {
+    /// Request body as is
+    Body,
+    /// JSON with `method`, `path`, `query`, `headers` and `body` of the request
+    Request,
+}
+
+impl Default for HttpTriggerPayloadConfig {
+    fn default() -> Self {
+        HttpTriggerPayloadConfig::Body
+    }
+}
+
+// Request
+#[derive(Debug, Clone)]
+pub struct HttpRequest {
+    pub method: Method,
+    pub path: String,
+    pub query: Vec<(String, String)>,
+    pub headers: Vec<(String, String)>,
+    pub body: Bytes,
+}
+
+impl HttpRequest {
+    fn to_json(&self) -> Value {
+        let body = serde_json::from_slice(&self.body)
+            .unwrap_or_else(|_| Value::String(String::from_utf8_lossy(&self.body).to_string()));
+
+        json!({
+            "method": self.method.as_str(),
+            "path": self.path,
+            "query": pairs_to_json(&self.query),
+            "headers": pairs_to_json(&self.headers),
+            "body": body,
+        })
+    }
+}
+
+fn pairs_to_json(pairs: &[(String, String)]) -> Value {
+    Value::Object(
+        pairs
+            .iter()
+            .map(|(key, value)| (key.clone(), Value::String(value.clone())))
+            .collect::<Map<String, Value>>(),
+    )
+}
+
+// Trigger
+#[derive(Debug, Clone)]
+pub struct HttpTrigger {
+    input_id: InputId,
+    trigger_id: TriggerId,
+    config: HttpTriggerConfig,
+}
+
+impl Display for HttpTrigger {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "HttpTrigger[{}::{}]", self.input_id, self.trigger_id)
+    }
+}
+
+impl HttpTrigger {
+    pub fn new(input_id: InputId, trigger_id: TriggerId, config: HttpTriggerConfig) -> Self {
+        Self {
+            input_id,
+            trigger_id,
+            config,
+        }
+    }
+
+    pub fn matches_path(&self, path: &str) -> bool {
+        self.config.path.trim_end_matches('/') == path.trim_end_matches('/')
+    }
+
+    pub fn matches_method(&self, method: &Method) -> bool {
+        self.config.method.eq_ignore_ascii_case(method.as_str())
+    }
+
+    pub fn process(&self, request: &HttpRequest) -> Option<TriggeredEvent> {
+        if !self.matches_path(&request.path) || !self.matches_method(&request.method) {
+            return None;
+        }
+
+        let payload = match self.config.payload {
+            HttpTriggerPayloadConfig::Body => request.body.clone(),
+            HttpTriggerPayloadConfig::Request => Bytes::from(request.to_json().to_string()),
+        };
+
+        Some(TriggeredEvent {
+            input: self.input_id.clone(),
+            trigger: self.trigger_id.clone(),
+            data: DataEvent {
+                payload,
+                meta: DataEventMeta::Http {
+                    method: request.method.to_string(),
+                    path: request.path.clone(),
+                    query: request.query.clone(),
+                    headers: request.headers.clone(),
+                },
+            },
+        })
+    }
+}
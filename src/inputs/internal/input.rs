@@ -0,0 +1,92 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+
+use async_trait::async_trait;
+use itertools::Itertools;
+use log::{trace, warn};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::broadcast::Receiver;
+use tokio::sync::mpsc::Sender;
+
+use crate::common::data::{InputId, TriggerId, TriggeredEvent};
+use crate::coordinator::BusEvent;
+use crate::health::ComponentHealth;
+use crate::inputs::internal::trigger::{InternalTrigger, InternalTriggerConfig};
+use crate::inputs::InputTask;
+
+// Config
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct InternalInputConfig {
+    #[serde(rename = "trigger")]
+    #[serde(default)]
+    pub triggers: HashMap<TriggerId, InternalTriggerConfig>,
+}
+
+#[derive(Debug)]
+pub struct InternalInput {
+    id: InputId,
+    triggers: Vec<InternalTrigger>,
+    health: ComponentHealth,
+    bus: Receiver<BusEvent>,
+}
+
+impl InternalInput {
+    /// Subscribes before the outputs are running, so no event is missed
+    pub fn new(
+        id: InputId,
+        config: InternalInputConfig,
+        health: ComponentHealth,
+        bus: Receiver<BusEvent>,
+    ) -> Self {
+        let triggers = config
+            .triggers
+            .into_iter()
+            .map(|(trigger_id, trigger_config)| {
+                InternalTrigger::new(id.clone(), trigger_id, trigger_config)
+            })
+            .collect_vec();
+        Self {
+            id,
+            triggers,
+            health,
+            bus,
+        }
+    }
+}
+
+impl Display for InternalInput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "InternalInput[{}]", self.id)
+    }
+}
+
+#[async_trait]
+impl InputTask for InternalInput {
+    async fn run(mut self: Box<Self>, chan: Sender<TriggeredEvent>) {
+        self.health.set_connected(true);
+
+        loop {
+            let event = match self.bus.recv().await {
+                Ok(event) => event,
+                Err(RecvError::Lagged(skipped)) => {
+                    warn!("{} is too slow, skipped {} events", self, skipped);
+                    continue;
+                }
+                Err(RecvError::Closed) => break,
+            };
+            trace!("{} received {:?}", self, event);
+
+            for trigger in &self.triggers {
+                if let Some(triggered_event) = trigger.process(&event) {
+                    trace!("{} processed the event", trigger);
+                    chan.send(triggered_event)
+                        .await
+                        .unwrap_or_else(|err| warn!("Can not send TriggeredEvent {:?}", &err));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,66 @@
+use std::fmt::{Display, Formatter};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::common::data::{DataEvent, DataEventMeta, InputId, TriggerId, TriggeredEvent};
+use crate::coordinator::BusEvent;
+use crate::inputs::filter::TriggerFilterConfig;
+
+// Config
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct InternalTriggerConfig {
+    /// Only events sent to the channel, all of them if not set
+    channel: Option<String>,
+    /// JavaScript filters get the channel as `topic`
+    #[serde(default)]
+    filter: TriggerFilterConfig,
+}
+
+// Trigger
+#[derive(Debug, Clone)]
+pub struct InternalTrigger {
+    input_id: InputId,
+    trigger_id: TriggerId,
+    config: InternalTriggerConfig,
+}
+
+impl Display for InternalTrigger {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "InternalTrigger[{}::{}]", self.input_id, self.trigger_id)
+    }
+}
+
+impl InternalTrigger {
+    pub fn new(input_id: InputId, trigger_id: TriggerId, config: InternalTriggerConfig) -> Self {
+        Self {
+            input_id,
+            trigger_id,
+            config,
+        }
+    }
+
+    pub fn process(&self, event: &BusEvent) -> Option<TriggeredEvent> {
+        if let Some(channel) = &self.config.channel {
+            if channel != &event.channel {
+                return None;
+            }
+        }
+        if !self.config.filter.matches(&event.channel, &event.payload) {
+            return None;
+        }
+
+        Some(TriggeredEvent {
+            input: self.input_id.clone(),
+            trigger: self.trigger_id.clone(),
+            data: DataEvent {
+                payload: event.payload.clone(),
+                meta: DataEventMeta::Internal {
+                    channel: event.channel.clone(),
+                    depth: event.depth,
+                },
+            },
+        })
+    }
+}
@@ -0,0 +1,162 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use itertools::Itertools;
+use log::{error, info, trace, warn};
+use rdkafka::consumer::{Consumer, StreamConsumer};
+use rdkafka::message::{BorrowedMessage, Message};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc::Sender;
+
+use crate::common::data::{InputId, TriggerId, TriggeredEvent};
+use crate::common::kafka::KafkaConnectionConfig;
+use crate::common::types::Result;
+use crate::health::ComponentHealth;
+use crate::inputs::kafka::trigger::{KafkaTrigger, KafkaTriggerConfig};
+use crate::inputs::InputTask;
+
+const RETRY_INTERVAL: Duration = Duration::from_secs(5);
+
+// Config
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct KafkaInputConfig {
+    #[serde(flatten)]
+    pub connection: KafkaConnectionConfig,
+    /// Consumer group, partitions are balanced between its members
+    group: String,
+    /// Start from the oldest message when the group has no committed offset yet
+    #[serde(default)]
+    from_beginning: bool,
+    #[serde(rename = "trigger")]
+    #[serde(default)]
+    pub triggers: HashMap<TriggerId, KafkaTriggerConfig>,
+}
+
+#[derive(Debug)]
+pub struct KafkaInput {
+    id: InputId,
+    triggers: Vec<KafkaTrigger>,
+    config: KafkaInputConfig,
+    health: ComponentHealth,
+}
+
+impl KafkaInput {
+    pub fn new(id: InputId, config: KafkaInputConfig, health: ComponentHealth) -> Self {
+        let triggers = config
+            .triggers
+            .clone()
+            .into_iter()
+            .map(|(trigger_id, trigger_config)| {
+                KafkaTrigger::new(id.clone(), trigger_id, trigger_config)
+            })
+            .collect_vec();
+        Self {
+            id,
+            triggers,
+            config,
+            health,
+        }
+    }
+
+    fn create_consumer(&self) -> Result<StreamConsumer> {
+        let offset_reset = if self.config.from_beginning {
+            "earliest"
+        } else {
+            "latest"
+        };
+        // Offsets are stored only after the message was dispatched, then committed in background
+        let consumer: StreamConsumer = self
+            .config
+            .connection
+            .client_config()
+            .set("group.id", &self.config.group)
+            .set("enable.auto.commit", "true")
+            .set("enable.auto.offset.store", "false")
+            .set("auto.offset.reset", offset_reset)
+            .create()?;
+
+        let topics = self
+            .triggers
+            .iter()
+            .map(|trigger| trigger.config.topic.as_str())
+            .unique()
+            .collect_vec();
+        consumer.subscribe(&topics)?;
+
+        Ok(consumer)
+    }
+
+    /// Returns whether every matching trigger has passed the message on
+    async fn dispatch(&self, message: &BorrowedMessage<'_>, chan: &Sender<TriggeredEvent>) -> bool {
+        trace!(
+            "{} received a message from {}[{}]@{}",
+            self,
+            message.topic(),
+            message.partition(),
+            message.offset()
+        );
+
+        let mut dispatched = true;
+        for trigger in &self.triggers {
+            if let Some(triggered_event) = trigger.process(message) {
+                trace!("{} processed the message", trigger);
+                if let Err(err) = chan.send(triggered_event).await {
+                    warn!("Can not send TriggeredEvent {:?}", &err);
+                    dispatched = false;
+                }
+            }
+        }
+
+        dispatched
+    }
+}
+
+impl Display for KafkaInput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "KafkaInput[{}]", self.id)
+    }
+}
+
+#[async_trait]
+impl InputTask for KafkaInput {
+    async fn run(self: Box<Self>, chan: Sender<TriggeredEvent>) {
+        let consumer = match self.create_consumer() {
+            Ok(consumer) => consumer,
+            Err(err) => {
+                error!("{} can not create a consumer: {}", self, err);
+                return;
+            }
+        };
+        info!(
+            "{} consuming from {} as {:?}",
+            self,
+            self.config.connection.brokers(),
+            self.config.group
+        );
+        // A quiet topic is no sign of trouble, only errors of `recv` are
+        self.health.set_connected(true);
+
+        // librdkafka reconnects by itself, errors are only reported here
+        loop {
+            match consumer.recv().await {
+                Ok(message) => {
+                    // Back after an error
+                    self.health.set_connected(true);
+                    if self.dispatch(&message, &chan).await {
+                        consumer
+                            .store_offset_from_message(&message)
+                            .unwrap_or_else(|err| warn!("{} can not store offset: {}", self, err));
+                    }
+                }
+                Err(err) => {
+                    warn!("{} {}, retrying in {:?}", self, err, RETRY_INTERVAL);
+                    self.health.set_connected(false);
+                    tokio::time::sleep(RETRY_INTERVAL).await;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use std::fmt::{Display, Formatter};
+
+use bytes::Bytes;
+use rdkafka::message::{BorrowedMessage, Headers, Message};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::common::data::{DataEvent, DataEventMeta, InputId, TriggerId, TriggeredEvent};
+use crate::inputs::filter::TriggerFilterConfig;
+
+// Config
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct KafkaTriggerConfig {
+    pub topic: String,
+    #[serde(default)]
+    filter: TriggerFilterConfig,
+}
+
+// Trigger
+#[derive(Debug, Clone)]
+pub struct KafkaTrigger {
+    input_id: InputId,
+    trigger_id: TriggerId,
+    pub config: KafkaTriggerConfig,
+}
+
+impl Display for KafkaTrigger {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "KafkaTrigger[{}::{}]", self.input_id, self.trigger_id)
+    }
+}
+
+impl KafkaTrigger {
+    pub fn new(input_id: InputId, trigger_id: TriggerId, config: KafkaTriggerConfig) -> Self {
+        Self {
+            input_id,
+            trigger_id,
+            config,
+        }
+    }
+
+    pub fn process(&self, message: &BorrowedMessage<'_>) -> Option<TriggeredEvent> {
+        let payload = message.payload().unwrap_or_default();
+        if message.topic() != self.config.topic
+            || !self.config.filter.matches(message.topic(), payload)
+        {
+            return None;
+        }
+
+        let headers = message
+            .headers()
+            .map(|headers| {
+                (0..headers.count())
+                    .filter_map(|idx| headers.get(idx))
+                    .map(|(name, value)| {
+                        (name.to_string(), String::from_utf8_lossy(value).to_string())
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Some(TriggeredEvent {
+            input: self.input_id.clone(),
+            trigger: self.trigger_id.clone(),
+            data: DataEvent {
+                payload: Bytes::copy_from_slice(payload),
+                meta: DataEventMeta::Kafka {
+                    topic: message.topic().to_string(),
+                    key: message
+                        .key()
+                        .map(|key| String::from_utf8_lossy(key).to_string()),
+                    partition: message.partition(),
+                    offset: message.offset(),
+                    headers,
+                },
+            },
+        })
+    }
+}
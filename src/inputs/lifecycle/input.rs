@@ -0,0 +1,128 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+
+use async_trait::async_trait;
+use itertools::Itertools;
+use log::{trace, warn};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::broadcast::Receiver;
+use tokio::sync::mpsc::Sender;
+
+use crate::common::data::{InputId, TriggerId, TriggeredEvent};
+use crate::common::types::Result;
+use crate::coordinator::LifecycleEvent;
+use crate::health::{ComponentChange, ComponentHealth, ComponentKind};
+use crate::inputs::lifecycle::trigger::{LifecycleTrigger, LifecycleTriggerConfig};
+use crate::inputs::InputTask;
+
+// Config
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct LifecycleInputConfig {
+    #[serde(rename = "trigger")]
+    #[serde(default)]
+    pub triggers: HashMap<TriggerId, LifecycleTriggerConfig>,
+}
+
+impl LifecycleInputConfig {
+    pub fn validate(&self) -> Result<()> {
+        for (trigger_id, trigger) in &self.triggers {
+            trigger
+                .validate()
+                .map_err(|err| format!("Trigger[{}]: {}", trigger_id, err))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct LifecycleInput {
+    id: InputId,
+    triggers: Vec<LifecycleTrigger>,
+    health: ComponentHealth,
+    lifecycle: Receiver<LifecycleEvent>,
+    changes: Receiver<ComponentChange>,
+}
+
+impl LifecycleInput {
+    /// Subscribes before the application emits `startup`, so it is not missed
+    pub fn new(
+        id: InputId,
+        config: LifecycleInputConfig,
+        health: ComponentHealth,
+        lifecycle: Receiver<LifecycleEvent>,
+        changes: Receiver<ComponentChange>,
+    ) -> Self {
+        let triggers = config
+            .triggers
+            .into_iter()
+            .map(|(trigger_id, trigger_config)| {
+                LifecycleTrigger::new(id.clone(), trigger_id, trigger_config)
+            })
+            .collect_vec();
+        Self {
+            id,
+            triggers,
+            health,
+            lifecycle,
+            changes,
+        }
+    }
+
+    async fn process(
+        &self,
+        event: LifecycleEvent,
+        input: Option<&InputId>,
+        chan: &Sender<TriggeredEvent>,
+    ) {
+        trace!("{} received {} of {:?}", self, event, input);
+
+        for trigger in &self.triggers {
+            if let Some(triggered_event) = trigger.process(event, input) {
+                trace!("{} processed the event", trigger);
+                chan.send(triggered_event)
+                    .await
+                    .unwrap_or_else(|err| warn!("Can not send TriggeredEvent {:?}", &err));
+            }
+        }
+    }
+}
+
+impl Display for LifecycleInput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "LifecycleInput[{}]", self.id)
+    }
+}
+
+#[async_trait]
+impl InputTask for LifecycleInput {
+    async fn run(mut self: Box<Self>, chan: Sender<TriggeredEvent>) {
+        self.health.set_connected(true);
+
+        loop {
+            tokio::select! {
+                received = self.lifecycle.recv() => match received {
+                    Ok(event) => self.process(event, None, &chan).await,
+                    Err(RecvError::Lagged(skipped)) => warn!("{} skipped {} events", self, skipped),
+                    Err(RecvError::Closed) => break,
+                },
+                received = self.changes.recv() => match received {
+                    Ok(change) if change.kind == ComponentKind::Input && change.id != self.id => {
+                        let event = if change.connected {
+                            LifecycleEvent::InputConnected
+                        } else {
+                            LifecycleEvent::InputDisconnected
+                        };
+                        self.process(event, Some(&change.id), &chan).await;
+                    }
+                    Ok(_) => {}
+                    Err(RecvError::Lagged(skipped)) => warn!("{} skipped {} connection changes", self, skipped),
+                    Err(RecvError::Closed) => break,
+                },
+            }
+        }
+    }
+}
@@ -0,0 +1,95 @@
+use std::fmt::{Display, Formatter};
+
+use bytes::Bytes;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::common::data::{DataEvent, DataEventMeta, InputId, TriggerId, TriggeredEvent};
+use crate::common::types::Result;
+use crate::coordinator::LifecycleEvent;
+
+// Config
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct LifecycleTriggerConfig {
+    event: LifecycleEvent,
+    /// Only connection changes of the input, any input if not set
+    input: Option<InputId>,
+}
+
+impl LifecycleTriggerConfig {
+    pub fn validate(&self) -> Result<()> {
+        let input_event = matches!(
+            self.event,
+            LifecycleEvent::InputConnected | LifecycleEvent::InputDisconnected
+        );
+        if self.input.is_some() && !input_event {
+            return Err(format!("`input` can not be set for `{}` events", self.event).into());
+        }
+
+        Ok(())
+    }
+}
+
+// Trigger
+#[derive(Debug, Clone)]
+pub struct LifecycleTrigger {
+    input_id: InputId,
+    trigger_id: TriggerId,
+    config: LifecycleTriggerConfig,
+}
+
+impl Display for LifecycleTrigger {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "LifecycleTrigger[{}::{}]",
+            self.input_id, self.trigger_id
+        )
+    }
+}
+
+impl LifecycleTrigger {
+    pub fn new(input_id: InputId, trigger_id: TriggerId, config: LifecycleTriggerConfig) -> Self {
+        Self {
+            input_id,
+            trigger_id,
+            config,
+        }
+    }
+
+    /// `input` is set for connection changes only
+    pub fn process(
+        &self,
+        event: LifecycleEvent,
+        input: Option<&InputId>,
+    ) -> Option<TriggeredEvent> {
+        if event != self.config.event {
+            return None;
+        }
+        if let Some(expected) = &self.config.input {
+            if input != Some(expected) {
+                return None;
+            }
+        }
+
+        let input = input.map(|input| input.to_string());
+        let payload = match &input {
+            Some(input) => json!({ "event": event, "input": input }),
+            None => json!({ "event": event }),
+        };
+
+        Some(TriggeredEvent {
+            input: self.input_id.clone(),
+            trigger: self.trigger_id.clone(),
+            data: DataEvent {
+                payload: Bytes::from(payload.to_string()),
+                meta: DataEventMeta::Lifecycle {
+                    event: event.to_string(),
+                    input,
+                },
+            },
+        })
+    }
+}
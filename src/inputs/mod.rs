@@ -1,4 +1,16 @@
+pub mod amqp;
+pub mod file;
+pub mod filter;
+pub mod http;
+pub mod internal;
+#[cfg(feature = "kafka")]
+pub mod kafka;
+pub mod lifecycle;
 pub mod mqtt;
+pub mod redis;
+pub mod schedule;
+pub mod socket;
+pub mod websocket;
 
 use crate::common::data::TriggeredEvent;
 use async_trait::async_trait;
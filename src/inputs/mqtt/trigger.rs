@@ -1,35 +1,26 @@
 use crate::common::data::DataEventMeta::MqttMetadata;
 use crate::common::data::{DataEvent, InputId, TriggerId, TriggeredEvent};
+use crate::common::mqtt::{topic_filter, topic_matches, validate_subscription};
 use crate::common::types::Result;
+use crate::inputs::filter::TriggerFilterConfig;
 use bytes::Bytes;
-use log::{error, warn};
 use paho_mqtt::Message;
-use rquickjs as rjs;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
 use std::fmt::{Display, Formatter};
-use std::str::FromStr;
 
 // MQTT
-#[derive(Debug, Default, PartialEq, Serialize, Deserialize, Clone)]
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize, Clone, JsonSchema)]
 pub struct MqttTriggerConfig {
+    /// Topic or a shared subscription, e.g. `$share/<group>/<topic>`
     pub topic: String,
     #[serde(default)]
-    filter: MqttTriggerFilterConfig,
+    filter: TriggerFilterConfig,
 }
 
-#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
-#[serde(tag = "type", rename_all = "snake_case")]
-enum MqttTriggerFilterConfig {
-    NoFilter,
-    DropAll,
-    Js { code: String },
-    Json { field: String, exact: String },
-}
-
-impl Default for MqttTriggerFilterConfig {
-    fn default() -> Self {
-        MqttTriggerFilterConfig::NoFilter
+impl MqttTriggerConfig {
+    pub fn validate(&self) -> Result<()> {
+        validate_subscription(&self.topic)
     }
 }
 
@@ -58,30 +49,9 @@ impl MqttTrigger {
     pub async fn process(&self, message: &Message) -> Option<TriggeredEvent> {
         let topic = String::from(message.topic());
 
-        let should_process = match topic.eq(&self.config.topic) {
+        let should_process = match topic_matches(topic_filter(&self.config.topic), &topic) {
             false => false,
-            true => match &self.config.filter {
-                MqttTriggerFilterConfig::NoFilter => true,
-                MqttTriggerFilterConfig::DropAll => false,
-                MqttTriggerFilterConfig::Js { code } => {
-                    process_js(code, &topic, message.payload().to_vec()).unwrap_or_else(|err| {
-                        error!("Can not process javascript filter code={}: {:?}", code, err);
-                        false
-                    })
-                }
-
-                MqttTriggerFilterConfig::Json { field, exact } => {
-                    process_json(field, exact, &topic, message.payload().to_vec()).unwrap_or_else(
-                        |err| {
-                            error!(
-                                "Can not process json filter field={:?}, exact={:?}: {:?}",
-                                field, exact, err
-                            );
-                            false
-                        },
-                    )
-                }
-            },
+            true => self.config.filter.matches(&topic, message.payload()),
         };
 
         if should_process {
@@ -100,66 +70,3 @@ impl MqttTrigger {
         }
     }
 }
-
-fn process_js(code: &str, topic: &str, payload: Vec<u8>) -> Result<bool> {
-    let rt = rjs::Runtime::new().unwrap();
-    let ctx = rjs::Context::full(&rt).unwrap();
-
-    let result: Result<bool> = ctx.with(|ctx| {
-        let func: rjs::Function = ctx.eval(format!(
-            "(topic, payload) => {{ return !!(() => {{ {} }})() }}",
-            code
-        ))?;
-
-        let topic_string = topic;
-        let payload_string = String::from_utf8(payload)?;
-
-        let result = func.call((topic_string, payload_string))?;
-        Ok(result)
-    });
-
-    result
-}
-
-fn process_json(field: &str, exact: &str, _topic: &str, payload: Vec<u8>) -> Result<bool> {
-    let payload_str = String::from_utf8(payload)?;
-
-    let json_value = serde_json::from_str(payload_str.as_str())?;
-
-    let result = match json_value {
-        Value::Null => {
-            if !field.is_empty() {
-                warn!("Field is not empty, but the value is Boolean");
-            }
-            Ok(exact.to_lowercase().eq("null"))
-        }
-        Value::Bool(value) => {
-            if !field.is_empty() {
-                warn!("Field is not empty, but the value is Boolean");
-            }
-            Ok(exact.to_lowercase().eq(value.to_string().as_str()))
-        }
-        Value::Number(value) => {
-            if !field.is_empty() {
-                warn!("Field is not empty, but the value is Number");
-            }
-            Ok(value.eq(&serde_json::Number::from_str(exact)?))
-        }
-        Value::String(value) => {
-            if !field.is_empty() {
-                warn!("Field is not empty, but the value is String");
-            }
-            Ok(value.eq(exact))
-        }
-        Value::Array(value) => value
-            .get(field.parse::<usize>()?)
-            .map(|value| value.eq(exact))
-            .ok_or_else(|| String::from("Invalid array field key")),
-        Value::Object(value) => value
-            .get(field)
-            .map(|value| value.eq(exact))
-            .ok_or_else(|| String::from("Invalid object field key")),
-    };
-
-    Ok(result?)
-}
@@ -0,0 +1,243 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use futures_util::StreamExt;
+use itertools::Itertools;
+use log::{debug, trace, warn};
+use redis::streams::{StreamReadOptions, StreamReadReply};
+use redis::{AsyncCommands, Client, Msg};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc::Sender;
+
+use crate::common::data::{InputId, TriggerId, TriggeredEvent};
+use crate::common::redis::RedisConnectionConfig;
+use crate::common::types::Result;
+use crate::common::utils::hostname;
+use crate::health::ComponentHealth;
+use crate::inputs::redis::trigger::{RedisTrigger, RedisTriggerConfig};
+use crate::inputs::InputTask;
+
+const RETRY_INTERVAL: Duration = Duration::from_secs(5);
+const STREAM_BLOCK: Duration = Duration::from_secs(5);
+const STREAM_BATCH: usize = 100;
+
+// Config
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct RedisInputConfig {
+    #[serde(flatten)]
+    pub connection: RedisConnectionConfig,
+    /// Consumer name used for stream groups, `<hostname>-<input id>` if not set, so that pending
+    /// messages are picked up again after a restart
+    consumer: Option<String>,
+    #[serde(rename = "trigger")]
+    #[serde(default)]
+    pub triggers: HashMap<TriggerId, RedisTriggerConfig>,
+}
+
+impl RedisInputConfig {
+    pub fn validate(&self) -> Result<()> {
+        for (trigger_id, trigger) in &self.triggers {
+            trigger
+                .validate()
+                .map_err(|err| format!("Trigger[{}]: {}", trigger_id, err))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct RedisInput {
+    id: InputId,
+    triggers: Vec<RedisTrigger>,
+    config: RedisInputConfig,
+    health: ComponentHealth,
+}
+
+impl RedisInput {
+    pub fn new(id: InputId, config: RedisInputConfig, health: ComponentHealth) -> Self {
+        let triggers = config
+            .triggers
+            .clone()
+            .into_iter()
+            .map(|(trigger_id, trigger_config)| {
+                RedisTrigger::new(id.clone(), trigger_id, trigger_config)
+            })
+            .collect_vec();
+        Self {
+            id,
+            triggers,
+            config,
+            health,
+        }
+    }
+}
+
+impl Display for RedisInput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "RedisInput[{}]", self.id)
+    }
+}
+
+#[async_trait]
+impl InputTask for RedisInput {
+    async fn run(self: Box<Self>, chan: Sender<TriggeredEvent>) {
+        let client = match self.config.connection.client() {
+            Ok(client) => client,
+            Err(err) => {
+                warn!("{} has an invalid connection config: {}", self, err);
+                return;
+            }
+        };
+
+        let name = self.to_string();
+        let consumer = self.config.consumer.clone().unwrap_or_else(|| {
+            format!(
+                "{}-{}",
+                hostname().unwrap_or_else(|| "mqrt".to_string()),
+                self.id
+            )
+        });
+        let (subscribers, streams): (Vec<_>, Vec<_>) = self
+            .triggers
+            .into_iter()
+            .partition(|trigger| trigger.config.stream.is_none());
+
+        // Every stream is read by its own connection, as XREADGROUP blocks it
+        let mut tasks = streams
+            .into_iter()
+            .map(|trigger| {
+                let name = name.clone();
+                let client = client.clone();
+                let consumer = consumer.clone();
+                let health = self.health.clone();
+                let chan = chan.clone();
+                tokio::spawn(async move {
+                    loop {
+                        if let Err(err) =
+                            read_stream(&client, &trigger, &consumer, &health, &chan).await
+                        {
+                            warn!("{} {}, retrying in {:?}", name, err, RETRY_INTERVAL);
+                        }
+                        health.set_connected(false);
+                        tokio::time::sleep(RETRY_INTERVAL).await;
+                    }
+                })
+            })
+            .collect_vec();
+
+        if !subscribers.is_empty() {
+            let subscribers = Arc::new(subscribers);
+            let health = self.health.clone();
+            tasks.push(tokio::spawn(async move {
+                loop {
+                    if let Err(err) = subscribe(&client, &subscribers, &health, &chan).await {
+                        warn!("{} {}, retrying in {:?}", name, err, RETRY_INTERVAL);
+                    }
+                    health.set_connected(false);
+                    tokio::time::sleep(RETRY_INTERVAL).await;
+                }
+            }));
+        }
+
+        futures_util::future::join_all(tasks).await;
+    }
+}
+
+async fn subscribe(
+    client: &Client,
+    triggers: &[RedisTrigger],
+    health: &ComponentHealth,
+    chan: &Sender<TriggeredEvent>,
+) -> Result<()> {
+    let mut pubsub = client.get_async_connection().await?.into_pubsub();
+    for trigger in triggers {
+        if let Some(channel) = &trigger.config.channel {
+            pubsub.subscribe(channel).await?;
+        }
+        if let Some(pattern) = &trigger.config.pattern {
+            pubsub.psubscribe(pattern).await?;
+        }
+    }
+    health.set_connected(true);
+    debug!(
+        "Subscribed to {} Redis channels and patterns",
+        triggers.len()
+    );
+
+    let mut messages = pubsub.on_message();
+    while let Some(message) = messages.next().await {
+        process_message(triggers, chan, &message).await;
+    }
+
+    Err("lost the connection".into())
+}
+
+async fn process_message(triggers: &[RedisTrigger], chan: &Sender<TriggeredEvent>, message: &Msg) {
+    let channel = message.get_channel_name();
+    let pattern = message.get_pattern::<Option<String>>().unwrap_or(None);
+    let payload = message.get_payload_bytes();
+    trace!("Redis message {:?} on {:?}", payload, channel);
+
+    for trigger in triggers {
+        if let Some(triggered_event) = trigger.process_message(channel, pattern.as_deref(), payload)
+        {
+            trace!("{} processed the message", trigger);
+            chan.send(triggered_event)
+                .await
+                .unwrap_or_else(|err| warn!("Can not send TriggeredEvent {:?}", &err));
+        }
+    }
+}
+
+/// Reads the stream as a group consumer, starting with the entries left pending by a previous run
+async fn read_stream(
+    client: &Client,
+    trigger: &RedisTrigger,
+    consumer: &str,
+    health: &ComponentHealth,
+    chan: &Sender<TriggeredEvent>,
+) -> Result<()> {
+    let stream = trigger.config.stream.as_deref().unwrap_or_default();
+    let group = trigger.config.group.as_deref().unwrap_or_default();
+
+    let mut conn = client.get_async_connection().await?;
+    let created: redis::RedisResult<()> = conn.xgroup_create_mkstream(stream, group, "$").await;
+    if let Err(err) = created {
+        if err.code() != Some("BUSYGROUP") {
+            return Err(err.into());
+        }
+    }
+    health.set_connected(true);
+    debug!("{} reading stream {:?} as {:?}", trigger, stream, consumer);
+
+    let mut pending = true;
+    loop {
+        let options = StreamReadOptions::default()
+            .group(group, consumer)
+            .count(STREAM_BATCH)
+            .block(STREAM_BLOCK.as_millis() as usize);
+        let from = if pending { "0" } else { ">" };
+        let reply: StreamReadReply = conn.xread_options(&[stream], &[from], &options).await?;
+
+        let entries = reply.keys.into_iter().flat_map(|key| key.ids).collect_vec();
+        if pending && entries.is_empty() {
+            pending = false;
+            continue;
+        }
+
+        for entry in entries {
+            if let Some(triggered_event) = trigger.process_entry(stream, &entry.id, &entry.map) {
+                trace!("{} processed entry {}", trigger, entry.id);
+                chan.send(triggered_event)
+                    .await
+                    .unwrap_or_else(|err| warn!("Can not send TriggeredEvent {:?}", &err));
+            }
+            let _: i64 = conn.xack(stream, group, &[&entry.id]).await?;
+        }
+    }
+}
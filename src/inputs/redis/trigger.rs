@@ -0,0 +1,142 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+
+use bytes::Bytes;
+use redis::Value as RedisValue;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::common::data::{DataEvent, DataEventMeta, InputId, TriggerId, TriggeredEvent};
+use crate::common::types::Result;
+use crate::inputs::filter::TriggerFilterConfig;
+
+// Config
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct RedisTriggerConfig {
+    /// Pub/sub channel
+    pub channel: Option<String>,
+    /// Pub/sub pattern like `sensors.*`
+    pub pattern: Option<String>,
+    /// Stream read by a consumer group
+    pub stream: Option<String>,
+    /// Consumer group of the stream, created if missing
+    pub group: Option<String>,
+    /// Stream entry field used as the payload, the whole entry as JSON if not set
+    field: Option<String>,
+    /// JavaScript filters get the channel or the stream as `topic`
+    #[serde(default)]
+    filter: TriggerFilterConfig,
+}
+
+impl RedisTriggerConfig {
+    pub fn validate(&self) -> Result<()> {
+        let sources = [&self.channel, &self.pattern, &self.stream]
+            .iter()
+            .filter(|source| source.is_some())
+            .count();
+        if sources != 1 {
+            return Err("Exactly one of `channel`, `pattern` and `stream` must be set".into());
+        }
+        if self.stream.is_some() != self.group.is_some() {
+            return Err("`group` must be set for `stream` triggers only".into());
+        }
+        if self.field.is_some() && self.stream.is_none() {
+            return Err("`field` can be set only for `stream` triggers".into());
+        }
+
+        Ok(())
+    }
+}
+
+// Trigger
+#[derive(Debug, Clone)]
+pub struct RedisTrigger {
+    input_id: InputId,
+    trigger_id: TriggerId,
+    pub config: RedisTriggerConfig,
+}
+
+impl Display for RedisTrigger {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "RedisTrigger[{}::{}]", self.input_id, self.trigger_id)
+    }
+}
+
+impl RedisTrigger {
+    pub fn new(input_id: InputId, trigger_id: TriggerId, config: RedisTriggerConfig) -> Self {
+        Self {
+            input_id,
+            trigger_id,
+            config,
+        }
+    }
+
+    /// `pattern` is set for messages received through a pattern subscription
+    pub fn process_message(
+        &self,
+        channel: &str,
+        pattern: Option<&str>,
+        payload: &[u8],
+    ) -> Option<TriggeredEvent> {
+        let matches = match pattern {
+            Some(pattern) => self.config.pattern.as_deref() == Some(pattern),
+            None => self.config.channel.as_deref() == Some(channel),
+        };
+        if !matches || !self.config.filter.matches(channel, payload) {
+            return None;
+        }
+
+        Some(self.event(channel, None, Bytes::copy_from_slice(payload)))
+    }
+
+    pub fn process_entry(
+        &self,
+        stream: &str,
+        id: &str,
+        fields: &HashMap<String, RedisValue>,
+    ) -> Option<TriggeredEvent> {
+        let payload = match &self.config.field {
+            Some(field) => Bytes::from(value_to_bytes(fields.get(field)?)),
+            None => {
+                let entry = fields
+                    .iter()
+                    .map(|(key, value)| {
+                        let value = String::from_utf8_lossy(&value_to_bytes(value)).to_string();
+                        (key.clone(), Value::String(value))
+                    })
+                    .collect::<Map<String, Value>>();
+                Bytes::from(Value::Object(entry).to_string())
+            }
+        };
+        if !self.config.filter.matches(stream, &payload) {
+            return None;
+        }
+
+        Some(self.event(stream, Some(id.to_string()), payload))
+    }
+
+    fn event(&self, channel: &str, id: Option<String>, payload: Bytes) -> TriggeredEvent {
+        TriggeredEvent {
+            input: self.input_id.clone(),
+            trigger: self.trigger_id.clone(),
+            data: DataEvent {
+                payload,
+                meta: DataEventMeta::Redis {
+                    channel: channel.to_string(),
+                    id,
+                },
+            },
+        }
+    }
+}
+
+fn value_to_bytes(value: &RedisValue) -> Vec<u8> {
+    match value {
+        RedisValue::Data(data) => data.clone(),
+        RedisValue::Int(value) => value.to_string().into_bytes(),
+        RedisValue::Status(value) => value.clone().into_bytes(),
+        _ => Vec::new(),
+    }
+}
@@ -0,0 +1,156 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use log::{error, info, trace, warn};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc::Sender;
+
+use crate::common::data::{InputId, TriggerId, TriggeredEvent};
+use crate::common::types::Result;
+use crate::health::ComponentHealth;
+use crate::inputs::schedule::sun::SunLocation;
+use crate::inputs::schedule::trigger::{ScheduleTimezone, ScheduleTrigger, ScheduleTriggerConfig};
+use crate::inputs::InputTask;
+
+/// Wall clock is re-checked at least this often, so clock adjustments are noticed
+const MAX_SLEEP: Duration = Duration::from_secs(60);
+
+/// Events scheduled further in the past than this are skipped instead of being fired late
+const MAX_DELAY_SECS: i64 = 1;
+
+// Config
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ScheduleInputConfig {
+    /// IANA name like `Europe/Berlin`, the local timezone of the host by default
+    timezone: Option<String>,
+    /// Location for `sun` triggers, degrees, north and east are positive
+    latitude: Option<f64>,
+    longitude: Option<f64>,
+    #[serde(rename = "trigger")]
+    #[serde(default)]
+    pub triggers: HashMap<TriggerId, ScheduleTriggerConfig>,
+}
+
+impl ScheduleInputConfig {
+    fn location(&self) -> Result<Option<SunLocation>> {
+        match (self.latitude, self.longitude) {
+            (Some(latitude), Some(longitude)) => {
+                if !(-90.0..=90.0).contains(&latitude) || !(-180.0..=180.0).contains(&longitude) {
+                    return Err(format!("Invalid location {}, {}", latitude, longitude).into());
+                }
+                Ok(Some(SunLocation {
+                    latitude,
+                    longitude,
+                }))
+            }
+            (None, None) => Ok(None),
+            _ => Err("Both `latitude` and `longitude` must be set".into()),
+        }
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        ScheduleTimezone::parse(self.timezone.as_deref())?;
+        let location = self.location()?;
+        for (trigger_id, trigger) in &self.triggers {
+            trigger
+                .validate(location)
+                .map_err(|err| format!("Trigger[{}]: {}", trigger_id, err))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct ScheduleInput {
+    id: InputId,
+    config: ScheduleInputConfig,
+    health: ComponentHealth,
+}
+
+impl ScheduleInput {
+    pub fn new(id: InputId, config: ScheduleInputConfig, health: ComponentHealth) -> Self {
+        Self { id, config, health }
+    }
+}
+
+impl Display for ScheduleInput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ScheduleInput[{}]", self.id)
+    }
+}
+
+#[async_trait]
+impl InputTask for ScheduleInput {
+    async fn run(self: Box<Self>, chan: Sender<TriggeredEvent>) {
+        let settings = ScheduleTimezone::parse(self.config.timezone.as_deref())
+            .and_then(|tz| self.config.location().map(|location| (tz, location)));
+        let (tz, location) = match settings {
+            Ok(settings) => settings,
+            Err(err) => {
+                error!("{}: {}", self, err);
+                return;
+            }
+        };
+
+        let mut tasks = Vec::new();
+        for (trigger_id, trigger_config) in self.config.triggers.clone() {
+            match ScheduleTrigger::new(self.id.clone(), trigger_id, trigger_config, location) {
+                Ok(trigger) => tasks.push(tokio::spawn(run_trigger(trigger, tz, chan.clone()))),
+                Err(err) => error!("{}: {}", self, err),
+            }
+        }
+
+        info!("{} started {} triggers", self, tasks.len());
+        self.health.set_connected(true);
+
+        for task in tasks {
+            task.await
+                .unwrap_or_else(|err| error!("{} trigger failed: {:?}", self, err));
+        }
+    }
+}
+
+async fn run_trigger(trigger: ScheduleTrigger, tz: ScheduleTimezone, chan: Sender<TriggeredEvent>) {
+    let mut last = Utc::now();
+
+    while let Some(next) = trigger.next_after(last, &tz) {
+        trace!("{} will fire at {}", trigger, tz.format(next));
+        sleep_until(next).await;
+
+        let now = Utc::now();
+        if (now - next).num_seconds() > MAX_DELAY_SECS {
+            warn!(
+                "{} skipped {}, it is already {}",
+                trigger,
+                tz.format(next),
+                tz.format(now)
+            );
+            last = now;
+            continue;
+        }
+
+        chan.send(trigger.process(next, &tz))
+            .await
+            .unwrap_or_else(|err| warn!("Can not send TriggeredEvent {:?}", &err));
+        last = next;
+    }
+
+    info!("{} will not fire anymore", trigger);
+}
+
+/// Sleeps until the wall clock reaches `time`
+async fn sleep_until(time: DateTime<Utc>) {
+    loop {
+        let remaining = match (time - Utc::now()).to_std() {
+            Ok(remaining) if !remaining.is_zero() => remaining,
+            _ => return,
+        };
+        tokio::time::sleep(remaining.min(MAX_SLEEP)).await;
+    }
+}
@@ -0,0 +1,6 @@
+mod input;
+mod sun;
+mod trigger;
+
+pub use input::ScheduleInput;
+pub use input::ScheduleInputConfig;
@@ -0,0 +1,240 @@
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Sun positions a trigger can fire at
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SunEvent {
+    AstronomicalDawn,
+    NauticalDawn,
+    CivilDawn,
+    Sunrise,
+    SolarNoon,
+    Sunset,
+    CivilDusk,
+    NauticalDusk,
+    AstronomicalDusk,
+}
+
+impl SunEvent {
+    /// Sun altitude in degrees and whether the sun is rising, `None` for the solar noon
+    fn altitude(&self) -> Option<(f64, bool)> {
+        match self {
+            SunEvent::AstronomicalDawn => Some((-18.0, true)),
+            SunEvent::NauticalDawn => Some((-12.0, true)),
+            SunEvent::CivilDawn => Some((-6.0, true)),
+            // Refraction and the size of the solar disk
+            SunEvent::Sunrise => Some((-0.833, true)),
+            SunEvent::SolarNoon => None,
+            SunEvent::Sunset => Some((-0.833, false)),
+            SunEvent::CivilDusk => Some((-6.0, false)),
+            SunEvent::NauticalDusk => Some((-12.0, false)),
+            SunEvent::AstronomicalDusk => Some((-18.0, false)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SunLocation {
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+impl SunLocation {
+    /// Time of the event on the UTC day starting at `day`, `None` if the sun does not reach
+    /// the altitude that day (polar day or night). Uses the sunrise equation, precise to a minute or so.
+    pub fn event_on(&self, day: DateTime<Utc>, event: SunEvent) -> Option<DateTime<Utc>> {
+        let days = (day - j2000()).num_days() as f64 + 0.0008;
+
+        // Mean solar time
+        let mean_time = days - self.longitude / 360.0;
+        let anomaly = (357.5291 + 0.985_600_28 * mean_time).rem_euclid(360.0);
+        let anomaly_rad = anomaly.to_radians();
+        let center = 1.9148 * anomaly_rad.sin()
+            + 0.02 * (2.0 * anomaly_rad).sin()
+            + 0.0003 * (3.0 * anomaly_rad).sin();
+        let ecliptic_longitude = (anomaly + center + 180.0 + 102.9372)
+            .rem_euclid(360.0)
+            .to_radians();
+        let transit =
+            mean_time + 0.0053 * anomaly_rad.sin() - 0.0069 * (2.0 * ecliptic_longitude).sin();
+
+        let time = match event.altitude() {
+            None => transit,
+            Some((altitude, rising)) => {
+                let declination =
+                    (ecliptic_longitude.sin() * 23.4397_f64.to_radians().sin()).asin();
+                let latitude = self.latitude.to_radians();
+                let cos_hour_angle = (altitude.to_radians().sin()
+                    - latitude.sin() * declination.sin())
+                    / (latitude.cos() * declination.cos());
+                if !(-1.0..=1.0).contains(&cos_hour_angle) {
+                    return None;
+                }
+
+                let hour_angle = cos_hour_angle.acos().to_degrees() / 360.0;
+                match rising {
+                    true => transit - hour_angle,
+                    false => transit + hour_angle,
+                }
+            }
+        };
+
+        // Solar time is counted from J2000 noon
+        Some(
+            j2000()
+                + Duration::hours(12)
+                + Duration::milliseconds((time * 86_400_000.0).round() as i64),
+        )
+    }
+
+    /// First event with the offset applied strictly after `after`
+    pub fn next_after(
+        &self,
+        event: SunEvent,
+        offset: Duration,
+        after: DateTime<Utc>,
+    ) -> Option<DateTime<Utc>> {
+        let shifted = after - offset;
+        let start =
+            shifted - Duration::seconds(shifted.timestamp().rem_euclid(86_400)) - Duration::days(1);
+
+        // A year covers the longest polar day or night
+        (0..370)
+            .filter_map(|day| self.event_on(start + Duration::days(day), event))
+            .map(|time| time + offset)
+            .find(|time| *time > after)
+    }
+}
+
+/// 2000-01-01 00:00 UTC
+fn j2000() -> DateTime<Utc> {
+    Utc.timestamp_opt(946_684_800, 0).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BERLIN: SunLocation = SunLocation {
+        latitude: 52.52,
+        longitude: 13.405,
+    };
+    const NEW_YORK: SunLocation = SunLocation {
+        latitude: 40.7128,
+        longitude: -74.006,
+    };
+    const TROMSO: SunLocation = SunLocation {
+        latitude: 69.6492,
+        longitude: 18.9553,
+    };
+
+    fn utc(time: &str) -> DateTime<Utc> {
+        time.parse().unwrap()
+    }
+
+    fn assert_close(actual: Option<DateTime<Utc>>, expected: &str) {
+        let actual = actual.expect("no event");
+        let diff = (actual - utc(expected)).num_seconds().abs();
+        assert!(diff <= 120, "{} is not close to {}", actual, expected);
+    }
+
+    #[test]
+    fn summer_solstice_in_berlin() {
+        let day = utc("2024-06-21T00:00:00Z");
+        assert_close(
+            BERLIN.event_on(day, SunEvent::Sunrise),
+            "2024-06-21T02:43:00Z",
+        );
+        assert_close(
+            BERLIN.event_on(day, SunEvent::SolarNoon),
+            "2024-06-21T11:08:00Z",
+        );
+        assert_close(
+            BERLIN.event_on(day, SunEvent::Sunset),
+            "2024-06-21T19:33:00Z",
+        );
+    }
+
+    #[test]
+    fn winter_solstice_in_new_york() {
+        let day = utc("2024-12-21T00:00:00Z");
+        assert_close(
+            NEW_YORK.event_on(day, SunEvent::Sunrise),
+            "2024-12-21T12:16:00Z",
+        );
+        assert_close(
+            NEW_YORK.event_on(day, SunEvent::Sunset),
+            "2024-12-21T21:32:00Z",
+        );
+    }
+
+    #[test]
+    fn next_after_skips_past_events() {
+        let after = utc("2024-06-21T12:00:00Z");
+        assert_close(
+            BERLIN.next_after(SunEvent::Sunrise, Duration::zero(), after),
+            "2024-06-22T02:43:00Z",
+        );
+        assert_close(
+            BERLIN.next_after(SunEvent::Sunset, Duration::zero(), after),
+            "2024-06-21T19:33:00Z",
+        );
+        assert_close(
+            BERLIN.next_after(SunEvent::Sunset, Duration::minutes(-30), after),
+            "2024-06-21T19:03:00Z",
+        );
+    }
+
+    #[test]
+    fn no_sunrise_in_polar_night() {
+        let day = utc("2024-12-21T00:00:00Z");
+        assert_eq!(TROMSO.event_on(day, SunEvent::Sunrise), None);
+        assert_eq!(TROMSO.event_on(day, SunEvent::Sunset), None);
+        assert!(TROMSO.event_on(day, SunEvent::CivilDawn).is_some());
+    }
+
+    #[test]
+    fn next_after_finds_the_end_of_polar_night() {
+        let sunrise = TROMSO
+            .next_after(
+                SunEvent::Sunrise,
+                Duration::zero(),
+                utc("2024-12-01T00:00:00Z"),
+            )
+            .unwrap();
+        assert!(sunrise > utc("2025-01-10T00:00:00Z"), "{}", sunrise);
+        assert!(sunrise < utc("2025-01-20T00:00:00Z"), "{}", sunrise);
+    }
+
+    #[test]
+    fn next_after_finds_the_end_of_polar_day() {
+        let sunset = TROMSO
+            .next_after(
+                SunEvent::Sunset,
+                Duration::zero(),
+                utc("2024-06-01T00:00:00Z"),
+            )
+            .unwrap();
+        assert!(sunset > utc("2024-07-15T00:00:00Z"), "{}", sunset);
+        assert!(sunset < utc("2024-07-30T00:00:00Z"), "{}", sunset);
+    }
+
+    #[test]
+    fn next_after_scans_half_a_year_near_the_pole() {
+        let location = SunLocation {
+            latitude: 89.5,
+            longitude: 0.0,
+        };
+        let sunrise = location
+            .next_after(
+                SunEvent::Sunrise,
+                Duration::zero(),
+                utc("2024-10-01T00:00:00Z"),
+            )
+            .unwrap();
+        assert!(sunrise > utc("2025-03-05T00:00:00Z"), "{}", sunrise);
+        assert!(sunrise < utc("2025-03-25T00:00:00Z"), "{}", sunrise);
+    }
+}
@@ -0,0 +1,343 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use std::time::Duration;
+
+use bytes::Bytes;
+use chrono::{DateTime, Local, SecondsFormat, TimeZone, Utc};
+use chrono_tz::Tz;
+use itertools::Itertools;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::common::data::{DataEvent, DataEventMeta, InputId, TriggerId, TriggeredEvent};
+use crate::common::template;
+use crate::common::types::Result;
+use crate::inputs::schedule::sun::{SunEvent, SunLocation};
+
+// Config
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ScheduleTriggerConfig {
+    /// Cron expression: `min hour day month weekday`, optionally with leading seconds
+    cron: Option<String>,
+    /// Fixed interval, the first event comes one interval after the start
+    every_secs: Option<u64>,
+    /// Sun position, needs `latitude` and `longitude` of the input
+    sun: Option<SunEvent>,
+    /// Shifts the sun event, e.g. `-30` fires half an hour before the sunset
+    #[serde(default)]
+    offset_mins: i64,
+    #[serde(default)]
+    payload: ScheduleTriggerPayloadConfig,
+}
+
+impl ScheduleTriggerConfig {
+    pub fn validate(&self, location: Option<SunLocation>) -> Result<()> {
+        ScheduleTiming::from_config(self, location).map(|_| ())
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ScheduleTriggerPayloadConfig {
+    Empty,
+    Static {
+        data: String,
+    },
+    /// JSON with `trigger`, `scheduled_at` and `timestamp`
+    Schedule,
+    /// `{{ placeholder }}` template, `scheduled_at` and `timestamp` are available
+    Template {
+        template: String,
+    },
+}
+
+impl Default for ScheduleTriggerPayloadConfig {
+    fn default() -> Self {
+        ScheduleTriggerPayloadConfig::Empty
+    }
+}
+
+// Timezone
+#[derive(Debug, Clone, Copy)]
+pub enum ScheduleTimezone {
+    Local,
+    Named(Tz),
+}
+
+impl ScheduleTimezone {
+    /// `None` means the local timezone of the host
+    pub fn parse(timezone: Option<&str>) -> Result<Self> {
+        match timezone {
+            None => Ok(ScheduleTimezone::Local),
+            Some(name) => Tz::from_str(name)
+                .map(ScheduleTimezone::Named)
+                .map_err(|err| format!("Unknown timezone {:?}: {}", name, err).into()),
+        }
+    }
+
+    fn cron_after(&self, schedule: &cron::Schedule, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match self {
+            ScheduleTimezone::Local => next_in(schedule, after, &Local),
+            ScheduleTimezone::Named(tz) => next_in(schedule, after, tz),
+        }
+    }
+
+    /// RFC 3339 time in this timezone
+    pub fn format(&self, time: DateTime<Utc>) -> String {
+        match self {
+            ScheduleTimezone::Local => time
+                .with_timezone(&Local)
+                .to_rfc3339_opts(SecondsFormat::Secs, false),
+            ScheduleTimezone::Named(tz) => time
+                .with_timezone(tz)
+                .to_rfc3339_opts(SecondsFormat::Secs, false),
+        }
+    }
+}
+
+fn next_in<Z: TimeZone>(
+    schedule: &cron::Schedule,
+    after: DateTime<Utc>,
+    tz: &Z,
+) -> Option<DateTime<Utc>> {
+    schedule
+        .after(&after.with_timezone(tz))
+        .next()
+        .map(|time| time.with_timezone(&Utc))
+}
+
+/// The cron crate expects seconds and counts weekdays from 1 (Sunday) to 7 (Saturday), the
+/// classic 5-field form with weekdays from 0 (Sunday) to 6, or 7 for Sunday, is accepted as well
+fn normalize_cron(expression: &str) -> String {
+    let fields = expression.split_whitespace().collect_vec();
+    match fields.as_slice() {
+        [minute, hour, day, month, weekday] => format!(
+            "0 {} {} {} {} {}",
+            minute,
+            hour,
+            day,
+            month,
+            normalize_weekdays(weekday)
+        ),
+        _ => expression.to_string(),
+    }
+}
+
+fn normalize_weekdays(field: &str) -> String {
+    field
+        .split(',')
+        .flat_map(normalize_weekday_item)
+        .unique()
+        .join(",")
+}
+
+/// Numeric days, ranges and steps are expanded into a list, as Sunday moves from the start of the
+/// week to both ends; names and `*` mean the same in both forms and are kept
+fn normalize_weekday_item(item: &str) -> Vec<String> {
+    let (range, step) = match item.split_once('/') {
+        Some((range, step)) => match step.parse::<usize>() {
+            Ok(step) if step > 0 => (range, step),
+            _ => return vec![item.to_string()],
+        },
+        None => (item, 1),
+    };
+    let bounds: Option<Vec<u32>> = range.split('-').map(|x| x.parse().ok()).collect();
+
+    let days = match bounds.as_deref() {
+        Some([day]) if step == 1 => vec![*day],
+        Some([first]) => (*first..=6).step_by(step).collect(),
+        Some([first, last]) => (*first..=*last).step_by(step).collect(),
+        _ => return vec![item.to_string()],
+    };
+    days.into_iter()
+        .map(|day| match day {
+            0 | 7 => 1,
+            day => day + 1,
+        })
+        .map(|day| day.to_string())
+        .collect()
+}
+
+// Timing
+#[derive(Debug, Clone)]
+enum ScheduleTiming {
+    Cron(Box<cron::Schedule>),
+    Every(Duration),
+    Sun {
+        event: SunEvent,
+        offset: chrono::Duration,
+        location: SunLocation,
+    },
+}
+
+impl ScheduleTiming {
+    fn from_config(config: &ScheduleTriggerConfig, location: Option<SunLocation>) -> Result<Self> {
+        if config.offset_mins != 0 && config.sun.is_none() {
+            return Err("`offset_mins` can be set only for `sun` triggers".into());
+        }
+
+        match (&config.cron, config.every_secs, config.sun) {
+            (Some(expression), None, None) => {
+                let schedule = cron::Schedule::from_str(&normalize_cron(expression))
+                    .map_err(|err| format!("Invalid cron expression {:?}: {}", expression, err))?;
+                Ok(ScheduleTiming::Cron(Box::new(schedule)))
+            }
+            (None, Some(0), None) => Err("`every_secs` must be positive".into()),
+            (None, Some(secs), None) => Ok(ScheduleTiming::Every(Duration::from_secs(secs))),
+            (None, None, Some(event)) => match location {
+                Some(location) => Ok(ScheduleTiming::Sun {
+                    event,
+                    offset: chrono::Duration::minutes(config.offset_mins),
+                    location,
+                }),
+                None => Err("`sun` triggers need `latitude` and `longitude` of the input".into()),
+            },
+            _ => Err("Exactly one of `cron`, `every_secs` and `sun` must be set".into()),
+        }
+    }
+}
+
+// Trigger
+#[derive(Debug, Clone)]
+pub struct ScheduleTrigger {
+    input_id: InputId,
+    trigger_id: TriggerId,
+    config: ScheduleTriggerConfig,
+    timing: ScheduleTiming,
+}
+
+impl Display for ScheduleTrigger {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ScheduleTrigger[{}::{}]", self.input_id, self.trigger_id)
+    }
+}
+
+impl ScheduleTrigger {
+    pub fn new(
+        input_id: InputId,
+        trigger_id: TriggerId,
+        config: ScheduleTriggerConfig,
+        location: Option<SunLocation>,
+    ) -> Result<Self> {
+        let timing = ScheduleTiming::from_config(&config, location)?;
+        Ok(Self {
+            input_id,
+            trigger_id,
+            config,
+            timing,
+        })
+    }
+
+    /// Next time the trigger fires strictly after `after`, `None` if it never fires again
+    pub fn next_after(&self, after: DateTime<Utc>, tz: &ScheduleTimezone) -> Option<DateTime<Utc>> {
+        match &self.timing {
+            ScheduleTiming::Cron(schedule) => tz.cron_after(schedule, after),
+            ScheduleTiming::Every(every) => chrono::Duration::from_std(*every)
+                .ok()
+                .map(|every| after + every),
+            ScheduleTiming::Sun {
+                event,
+                offset,
+                location,
+            } => location.next_after(*event, *offset, after),
+        }
+    }
+
+    pub fn process(&self, scheduled_at: DateTime<Utc>, tz: &ScheduleTimezone) -> TriggeredEvent {
+        let meta = DataEventMeta::Schedule {
+            scheduled_at: tz.format(scheduled_at),
+            timestamp: scheduled_at.timestamp(),
+        };
+
+        let payload = match &self.config.payload {
+            ScheduleTriggerPayloadConfig::Empty => Bytes::new(),
+            ScheduleTriggerPayloadConfig::Static { data } => Bytes::from(data.clone()),
+            ScheduleTriggerPayloadConfig::Schedule => Bytes::from(
+                json!({
+                    "trigger": self.trigger_id.to_string(),
+                    "scheduled_at": tz.format(scheduled_at),
+                    "timestamp": scheduled_at.timestamp(),
+                })
+                .to_string(),
+            ),
+            ScheduleTriggerPayloadConfig::Template { template } => {
+                let data = DataEvent {
+                    meta: meta.clone(),
+                    payload: Bytes::new(),
+                };
+                Bytes::from(template::render(template, &data))
+            }
+        };
+
+        TriggeredEvent {
+            input: self.input_id.clone(),
+            trigger: self.trigger_id.clone(),
+            data: DataEvent { meta, payload },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_expressions_with_seconds() {
+        assert_eq!(normalize_cron("30 0 23 * * 1"), "30 0 23 * * 1");
+    }
+
+    #[test]
+    fn adds_seconds_to_classic_expressions() {
+        assert_eq!(normalize_cron("0 23 * * *"), "0 0 23 * * *");
+        assert_eq!(normalize_cron("*/5 8-18 1,15 * *"), "0 */5 8-18 1,15 * *");
+    }
+
+    #[test]
+    fn shifts_classic_weekdays() {
+        assert_eq!(normalize_cron("0 9 * * 0"), "0 0 9 * * 1");
+        assert_eq!(normalize_cron("0 9 * * 7"), "0 0 9 * * 1");
+        assert_eq!(normalize_cron("0 9 * * 1"), "0 0 9 * * 2");
+        assert_eq!(normalize_cron("0 9 * * 6"), "0 0 9 * * 7");
+    }
+
+    #[test]
+    fn shifts_weekday_lists_and_ranges() {
+        assert_eq!(normalize_weekdays("1-5"), "2,3,4,5,6");
+        assert_eq!(normalize_weekdays("0,6"), "1,7");
+        assert_eq!(normalize_weekdays("5-7"), "6,7,1");
+        assert_eq!(normalize_weekdays("0-6/2"), "1,3,5,7");
+        assert_eq!(normalize_weekdays("1/2"), "2,4,6");
+        assert_eq!(normalize_weekdays("0,7"), "1");
+    }
+
+    #[test]
+    fn keeps_weekday_names_and_wildcards() {
+        assert_eq!(normalize_weekdays("MON-FRI"), "MON-FRI");
+        assert_eq!(normalize_weekdays("Sun,Sat"), "Sun,Sat");
+        assert_eq!(normalize_weekdays("*"), "*");
+        assert_eq!(normalize_weekdays("*/2"), "*/2");
+        assert_eq!(normalize_weekdays("SUN,3"), "SUN,4");
+    }
+
+    #[test]
+    fn classic_weekdays_fire_on_the_right_day() {
+        use chrono::{Datelike, Weekday};
+
+        // 2024-01-01 is a Monday
+        let after: DateTime<Utc> = "2024-01-01T12:00:00Z".parse().unwrap();
+        let next = |expression: &str| {
+            let schedule = cron::Schedule::from_str(&normalize_cron(expression)).unwrap();
+            next_in(&schedule, after, &Utc).unwrap().weekday()
+        };
+
+        assert_eq!(next("0 9 * * 0"), Weekday::Sun);
+        assert_eq!(next("0 9 * * 7"), Weekday::Sun);
+        assert_eq!(next("0 9 * * 1"), Weekday::Mon);
+        assert_eq!(next("0 9 * * 5"), Weekday::Fri);
+        assert_eq!(next("0 9 * * 6"), Weekday::Sat);
+        assert_eq!(next("0 9 * * 1-5"), Weekday::Tue);
+        assert_eq!(next("0 9 * * SAT"), Weekday::Sat);
+    }
+}
@@ -0,0 +1,8 @@
+mod trigger;
+mod udp;
+mod unix;
+
+pub use udp::UdpInput;
+pub use udp::UdpInputConfig;
+pub use unix::UnixSocketInput;
+pub use unix::UnixSocketInputConfig;
@@ -0,0 +1,75 @@
+use std::fmt::{Display, Formatter};
+
+use bytes::Bytes;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::common::data::{DataEvent, DataEventMeta, InputId, TriggerId, TriggeredEvent};
+use crate::inputs::filter::TriggerFilterConfig;
+
+// Config
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct SocketTriggerConfig {
+    /// Only messages from this peer: `192.168.1.20` or `192.168.1.20:5000` for UDP,
+    /// `uid=1000` (or `gid=`, `pid=`) for Unix sockets
+    peer: Option<String>,
+    #[serde(default)]
+    filter: TriggerFilterConfig,
+}
+
+// Trigger
+#[derive(Debug, Clone)]
+pub struct SocketTrigger {
+    input_id: InputId,
+    trigger_id: TriggerId,
+    config: SocketTriggerConfig,
+}
+
+impl Display for SocketTrigger {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "SocketTrigger[{}::{}]", self.input_id, self.trigger_id)
+    }
+}
+
+impl SocketTrigger {
+    pub fn new(input_id: InputId, trigger_id: TriggerId, config: SocketTriggerConfig) -> Self {
+        Self {
+            input_id,
+            trigger_id,
+            config,
+        }
+    }
+
+    fn matches_peer(&self, peer: &str) -> bool {
+        match &self.config.peer {
+            None => true,
+            Some(expected) => {
+                let host = peer
+                    .rsplit_once(':')
+                    .map(|(host, _)| host.trim_matches(&['[', ']'][..]));
+                peer == expected
+                    || host == Some(expected.as_str())
+                    || peer.split(',').any(|part| part == expected)
+            }
+        }
+    }
+
+    /// `peer` is passed to JavaScript filters as `topic`
+    pub fn process(&self, peer: &str, payload: &[u8]) -> Option<TriggeredEvent> {
+        if !self.matches_peer(peer) || !self.config.filter.matches(peer, payload) {
+            return None;
+        }
+
+        Some(TriggeredEvent {
+            input: self.input_id.clone(),
+            trigger: self.trigger_id.clone(),
+            data: DataEvent {
+                payload: Bytes::copy_from_slice(payload),
+                meta: DataEventMeta::Socket {
+                    peer: peer.to_string(),
+                },
+            },
+        })
+    }
+}
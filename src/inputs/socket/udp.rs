@@ -0,0 +1,100 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::net::SocketAddr;
+
+use async_trait::async_trait;
+use itertools::Itertools;
+use log::{error, info, trace, warn};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tokio::net::UdpSocket;
+use tokio::sync::mpsc::Sender;
+
+use crate::common::data::{InputId, TriggerId, TriggeredEvent};
+use crate::health::ComponentHealth;
+use crate::inputs::socket::trigger::{SocketTrigger, SocketTriggerConfig};
+use crate::inputs::InputTask;
+
+/// Largest possible UDP payload
+const MAX_DATAGRAM_SIZE: usize = 65_507;
+
+// Config
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct UdpInputConfig {
+    listen: SocketAddr,
+    #[serde(rename = "trigger")]
+    #[serde(default)]
+    pub triggers: HashMap<TriggerId, SocketTriggerConfig>,
+}
+
+#[derive(Debug)]
+pub struct UdpInput {
+    id: InputId,
+    triggers: Vec<SocketTrigger>,
+    config: UdpInputConfig,
+    health: ComponentHealth,
+}
+
+impl UdpInput {
+    pub fn new(id: InputId, config: UdpInputConfig, health: ComponentHealth) -> Self {
+        let triggers = config
+            .triggers
+            .clone()
+            .into_iter()
+            .map(|(trigger_id, trigger_config)| {
+                SocketTrigger::new(id.clone(), trigger_id, trigger_config)
+            })
+            .collect_vec();
+        Self {
+            id,
+            triggers,
+            config,
+            health,
+        }
+    }
+}
+
+impl Display for UdpInput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "UdpInput[{}]", self.id)
+    }
+}
+
+#[async_trait]
+impl InputTask for UdpInput {
+    async fn run(self: Box<Self>, chan: Sender<TriggeredEvent>) {
+        let socket = match UdpSocket::bind(self.config.listen).await {
+            Ok(socket) => socket,
+            Err(err) => {
+                error!("{} can not bind to {}: {:?}", self, self.config.listen, err);
+                return;
+            }
+        };
+
+        info!("{} listening on udp://{}", self, self.config.listen);
+        self.health.set_connected(true);
+
+        let mut buffer = vec![0; MAX_DATAGRAM_SIZE];
+        loop {
+            let (size, peer) = match socket.recv_from(&mut buffer).await {
+                Ok(received) => received,
+                Err(err) => {
+                    warn!("{} can not receive a datagram: {:?}", self, err);
+                    continue;
+                }
+            };
+            let peer = peer.to_string();
+            trace!("{} received {} bytes from {}", self, size, peer);
+
+            for trigger in &self.triggers {
+                if let Some(triggered_event) = trigger.process(&peer, &buffer[..size]) {
+                    trace!("{} processed the datagram", trigger);
+                    chan.send(triggered_event)
+                        .await
+                        .unwrap_or_else(|err| warn!("Can not send TriggeredEvent {:?}", &err));
+                }
+            }
+        }
+    }
+}
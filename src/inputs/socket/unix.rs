@@ -0,0 +1,175 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::os::unix::fs::PermissionsExt;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use itertools::Itertools;
+use log::{debug, error, info, trace, warn};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::net::{UnixListener, UnixStream};
+use tokio::sync::mpsc::Sender;
+
+use crate::common::data::{InputId, TriggerId, TriggeredEvent};
+use crate::common::types::Result;
+use crate::health::ComponentHealth;
+use crate::inputs::socket::trigger::{SocketTrigger, SocketTriggerConfig};
+use crate::inputs::InputTask;
+
+// Config
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct UnixSocketInputConfig {
+    /// Socket file, a stale one left by a previous run is replaced
+    path: String,
+    /// Permissions of the socket file as an octal string, e.g. `"0660"`
+    mode: Option<String>,
+    #[serde(rename = "trigger")]
+    #[serde(default)]
+    pub triggers: HashMap<TriggerId, SocketTriggerConfig>,
+}
+
+impl UnixSocketInputConfig {
+    pub fn validate(&self) -> Result<()> {
+        self.mode().map(|_| ())
+    }
+
+    fn mode(&self) -> Result<Option<u32>> {
+        match &self.mode {
+            None => Ok(None),
+            Some(mode) => u32::from_str_radix(mode, 8).map(Some).map_err(|_| {
+                format!("Invalid mode {:?}, expected an octal like \"0660\"", mode).into()
+            }),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct UnixSocketInput {
+    id: InputId,
+    triggers: Vec<SocketTrigger>,
+    config: UnixSocketInputConfig,
+    health: ComponentHealth,
+}
+
+impl UnixSocketInput {
+    pub fn new(id: InputId, config: UnixSocketInputConfig, health: ComponentHealth) -> Self {
+        let triggers = config
+            .triggers
+            .clone()
+            .into_iter()
+            .map(|(trigger_id, trigger_config)| {
+                SocketTrigger::new(id.clone(), trigger_id, trigger_config)
+            })
+            .collect_vec();
+        Self {
+            id,
+            triggers,
+            config,
+            health,
+        }
+    }
+
+    fn bind(&self) -> Result<UnixListener> {
+        // Only a socket is replaced, never a regular file
+        if let Ok(metadata) = std::fs::symlink_metadata(&self.config.path) {
+            use std::os::unix::fs::FileTypeExt;
+            if metadata.file_type().is_socket() {
+                std::fs::remove_file(&self.config.path)?;
+            }
+        }
+
+        let listener = UnixListener::bind(&self.config.path)?;
+        if let Some(mode) = self.config.mode()? {
+            std::fs::set_permissions(&self.config.path, std::fs::Permissions::from_mode(mode))?;
+        }
+
+        Ok(listener)
+    }
+}
+
+impl Display for UnixSocketInput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "UnixSocketInput[{}]", self.id)
+    }
+}
+
+#[async_trait]
+impl InputTask for UnixSocketInput {
+    async fn run(self: Box<Self>, chan: Sender<TriggeredEvent>) {
+        let listener = match self.bind() {
+            Ok(listener) => listener,
+            Err(err) => {
+                error!("{} can not bind to {}: {:?}", self, self.config.path, err);
+                return;
+            }
+        };
+
+        info!("{} listening on unix://{}", self, self.config.path);
+        self.health.set_connected(true);
+
+        let name = self.to_string();
+        let triggers = Arc::new(self.triggers);
+        loop {
+            let stream = match listener.accept().await {
+                Ok((stream, _)) => stream,
+                Err(err) => {
+                    warn!("{} can not accept a connection: {:?}", name, err);
+                    continue;
+                }
+            };
+
+            let triggers = triggers.clone();
+            let chan = chan.clone();
+            let name = name.clone();
+            tokio::spawn(async move {
+                read_lines(&name, stream, &triggers, chan).await;
+            });
+        }
+    }
+}
+
+async fn read_lines(
+    name: &str,
+    stream: UnixStream,
+    triggers: &[SocketTrigger],
+    chan: Sender<TriggeredEvent>,
+) {
+    let peer = peer_of(&stream);
+    debug!("{} accepted a connection from {}", name, peer);
+
+    let mut lines = BufReader::new(stream).lines();
+    loop {
+        let line = match lines.next_line().await {
+            Ok(Some(line)) => line,
+            Ok(None) => break,
+            Err(err) => {
+                warn!("{} can not read from {}: {:?}", name, peer, err);
+                break;
+            }
+        };
+        trace!("{} received {:?} from {}", name, line, peer);
+
+        for trigger in triggers {
+            if let Some(triggered_event) = trigger.process(&peer, line.as_bytes()) {
+                trace!("{} processed the line", trigger);
+                chan.send(triggered_event)
+                    .await
+                    .unwrap_or_else(|err| warn!("Can not send TriggeredEvent {:?}", &err));
+            }
+        }
+    }
+}
+
+/// Credentials of the connected process, e.g. `uid=1000,gid=1000,pid=4242`
+fn peer_of(stream: &UnixStream) -> String {
+    match stream.peer_cred() {
+        Ok(cred) => match cred.pid() {
+            Some(pid) => format!("uid={},gid={},pid={}", cred.uid(), cred.gid(), pid),
+            None => format!("uid={},gid={}", cred.uid(), cred.gid()),
+        },
+        Err(_) => String::from("unknown"),
+    }
+}
@@ -0,0 +1,157 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures_util::StreamExt;
+use itertools::Itertools;
+use log::{debug, error, info, trace, warn};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc::Sender;
+use tokio_tungstenite::tungstenite::Message;
+
+use crate::common::data::{InputId, TriggerId, TriggeredEvent};
+use crate::common::secret::Secret;
+use crate::common::websocket::{self, ChannelMessage};
+use crate::health::ComponentHealth;
+use crate::inputs::websocket::trigger::{WebSocketTrigger, WebSocketTriggerConfig};
+use crate::inputs::InputTask;
+
+// Config
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct WebSocketInputConfig {
+    listen: SocketAddr,
+    /// Require the token in `Authorization: Bearer <token>` or `?token=<token>`
+    token: Option<Secret>,
+    #[serde(rename = "trigger")]
+    #[serde(default)]
+    pub triggers: HashMap<TriggerId, WebSocketTriggerConfig>,
+}
+
+#[derive(Debug)]
+pub struct WebSocketInput {
+    id: InputId,
+    triggers: Vec<WebSocketTrigger>,
+    config: WebSocketInputConfig,
+    health: ComponentHealth,
+}
+
+impl WebSocketInput {
+    pub fn new(id: InputId, config: WebSocketInputConfig, health: ComponentHealth) -> Self {
+        let triggers = config
+            .triggers
+            .clone()
+            .into_iter()
+            .map(|(trigger_id, trigger_config)| {
+                WebSocketTrigger::new(id.clone(), trigger_id, trigger_config)
+            })
+            .collect_vec();
+        Self {
+            id,
+            triggers,
+            config,
+            health,
+        }
+    }
+}
+
+impl Display for WebSocketInput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "WebSocketInput[{}]", self.id)
+    }
+}
+
+#[async_trait]
+impl InputTask for WebSocketInput {
+    async fn run(self: Box<Self>, chan: Sender<TriggeredEvent>) {
+        let listener = match TcpListener::bind(self.config.listen).await {
+            Ok(listener) => listener,
+            Err(err) => {
+                error!("{} can not bind to {}: {:?}", self, self.config.listen, err);
+                return;
+            }
+        };
+
+        info!("{} listening on ws://{}", self, self.config.listen);
+        self.health.set_connected(true);
+
+        let name = self.to_string();
+        let token = self.config.token.clone();
+        let triggers = Arc::new(self.triggers);
+        loop {
+            let (stream, peer) = match listener.accept().await {
+                Ok(accepted) => accepted,
+                Err(err) => {
+                    warn!("{} can not accept a connection: {:?}", name, err);
+                    continue;
+                }
+            };
+
+            let name = name.clone();
+            let token = token.clone();
+            let triggers = triggers.clone();
+            let chan = chan.clone();
+            tokio::spawn(async move {
+                serve_client(&name, stream, peer, token.as_ref(), &triggers, chan).await;
+            });
+        }
+    }
+}
+
+async fn serve_client(
+    name: &str,
+    stream: TcpStream,
+    peer: SocketAddr,
+    token: Option<&Secret>,
+    triggers: &[WebSocketTrigger],
+    chan: Sender<TriggeredEvent>,
+) {
+    let (mut websocket, _) = match websocket::accept(stream, token).await {
+        Ok(accepted) => accepted,
+        Err(err) => {
+            debug!("{} rejected {}: {}", name, peer, err);
+            return;
+        }
+    };
+    debug!("{} accepted {}", name, peer);
+
+    let peer = peer.to_string();
+    while let Some(message) = websocket.next().await {
+        let (channel, payload) = match message {
+            Ok(Message::Text(text)) => match serde_json::from_str::<ChannelMessage>(&text) {
+                Ok(message) => (message.channel.clone(), message.payload_bytes()),
+                Err(_) => (String::new(), Bytes::from(text)),
+            },
+            Ok(Message::Binary(data)) => (String::new(), Bytes::from(data)),
+            Ok(Message::Close(_)) => break,
+            Ok(_) => continue,
+            Err(err) => {
+                debug!("{} lost {}: {}", name, peer, err);
+                break;
+            }
+        };
+        trace!(
+            "{} received {:?} on {:?} from {}",
+            name,
+            payload,
+            channel,
+            peer
+        );
+
+        for trigger in triggers {
+            if let Some(triggered_event) = trigger.process(&peer, &channel, &payload) {
+                trace!("{} processed the message", trigger);
+                chan.send(triggered_event)
+                    .await
+                    .unwrap_or_else(|err| warn!("Can not send TriggeredEvent {:?}", &err));
+            }
+        }
+    }
+
+    debug!("{} disconnected {}", name, peer);
+}
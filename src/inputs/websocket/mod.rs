@@ -0,0 +1,5 @@
+mod input;
+mod trigger;
+
+pub use input::WebSocketInput;
+pub use input::WebSocketInputConfig;
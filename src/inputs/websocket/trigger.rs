@@ -0,0 +1,71 @@
+use std::fmt::{Display, Formatter};
+
+use bytes::Bytes;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::common::data::{DataEvent, DataEventMeta, InputId, TriggerId, TriggeredEvent};
+use crate::inputs::filter::TriggerFilterConfig;
+
+// Config
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct WebSocketTriggerConfig {
+    /// Only messages sent to the channel, every message matches if not set
+    channel: Option<String>,
+    /// JavaScript filters get the channel as `topic`
+    #[serde(default)]
+    filter: TriggerFilterConfig,
+}
+
+// Trigger
+#[derive(Debug, Clone)]
+pub struct WebSocketTrigger {
+    input_id: InputId,
+    trigger_id: TriggerId,
+    config: WebSocketTriggerConfig,
+}
+
+impl Display for WebSocketTrigger {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "WebSocketTrigger[{}::{}]",
+            self.input_id, self.trigger_id
+        )
+    }
+}
+
+impl WebSocketTrigger {
+    pub fn new(input_id: InputId, trigger_id: TriggerId, config: WebSocketTriggerConfig) -> Self {
+        Self {
+            input_id,
+            trigger_id,
+            config,
+        }
+    }
+
+    /// `channel` is empty for messages that are not `{"channel": ..., "payload": ...}`
+    pub fn process(&self, peer: &str, channel: &str, payload: &Bytes) -> Option<TriggeredEvent> {
+        if let Some(expected) = &self.config.channel {
+            if expected != channel {
+                return None;
+            }
+        }
+        if !self.config.filter.matches(channel, payload) {
+            return None;
+        }
+
+        Some(TriggeredEvent {
+            input: self.input_id.clone(),
+            trigger: self.trigger_id.clone(),
+            data: DataEvent {
+                payload: payload.clone(),
+                meta: DataEventMeta::WebSocket {
+                    channel: channel.to_string(),
+                    peer: peer.to_string(),
+                },
+            },
+        })
+    }
+}
@@ -1,6 +1,8 @@
+pub mod api;
 pub mod app;
 pub mod common;
 pub mod config;
 pub mod coordinator;
+pub mod health;
 pub mod inputs;
 pub mod outputs;
@@ -1,7 +1,15 @@
 extern crate mqrt;
 
-use mqrt::app::Application;
+use mqrt::app::{execute_command, Application};
+use mqrt::config::opt::Opt;
+use structopt::StructOpt;
 
 fn main() {
-    Application::new().run();
+    env_logger::init();
+
+    let opt = Opt::from_args();
+    match opt.command.clone() {
+        Some(command) => execute_command(&opt, &command),
+        None => Application::new(opt).run(),
+    }
 }
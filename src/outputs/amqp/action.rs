@@ -0,0 +1,83 @@
+use std::fmt::{Display, Formatter};
+
+use lapin::BasicProperties;
+use log::info;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::common::data::{ActionId, ActionableEvent, OutputId};
+use crate::common::template;
+use crate::outputs::payload::ActionPayloadConfig;
+
+// Config
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct AmqpActionConfig {
+    /// The default exchange routes messages to the queue named by the routing key
+    #[serde(default)]
+    exchange: String,
+    /// `{{ placeholder }}` template, e.g. `devices.{{ topic }}`
+    routing_key: String,
+    /// Ask the broker to store messages on disk
+    #[serde(default = "default_persistent")]
+    persistent: bool,
+    content_type: Option<String>,
+    #[serde(default)]
+    payload: ActionPayloadConfig,
+}
+
+fn default_persistent() -> bool {
+    true
+}
+
+/// Message built by an action, ready to be published
+#[derive(Debug, Clone)]
+pub struct AmqpMessage {
+    pub exchange: String,
+    pub routing_key: String,
+    pub properties: BasicProperties,
+    pub payload: Vec<u8>,
+}
+
+// Action
+#[derive(Debug, Clone)]
+pub struct AmqpAction {
+    output_id: OutputId,
+    pub action_id: ActionId,
+    config: AmqpActionConfig,
+}
+
+impl Display for AmqpAction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "AmqpAction[{}::{}]", self.output_id, self.action_id)
+    }
+}
+
+impl AmqpAction {
+    pub fn new(output_id: OutputId, action_id: ActionId, config: AmqpActionConfig) -> Self {
+        Self {
+            output_id,
+            action_id,
+            config,
+        }
+    }
+
+    pub async fn process(&self, event: &ActionableEvent) -> Option<AmqpMessage> {
+        info!("Amqp Action {} received {:?}", self.action_id, event);
+
+        let mut properties = BasicProperties::default();
+        if self.config.persistent {
+            properties = properties.with_delivery_mode(2);
+        }
+        if let Some(content_type) = &self.config.content_type {
+            properties = properties.with_content_type(content_type.as_str().into());
+        }
+
+        Some(AmqpMessage {
+            exchange: self.config.exchange.clone(),
+            routing_key: template::render(&self.config.routing_key, &event.data),
+            properties,
+            payload: self.config.payload.build(&event.data),
+        })
+    }
+}
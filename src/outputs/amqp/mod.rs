@@ -0,0 +1,5 @@
+mod action;
+mod output;
+
+pub use output::AmqpOutput;
+pub use output::AmqpOutputConfig;
@@ -0,0 +1,155 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+
+use async_trait::async_trait;
+use itertools::Itertools;
+use lapin::options::{BasicPublishOptions, ConfirmSelectOptions};
+use lapin::{Channel, Connection};
+use log::{info, trace, warn};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc::Receiver;
+
+use crate::common::amqp::AmqpConnectionConfig;
+use crate::common::data::{ActionId, ActionableEvent, ElId};
+use crate::common::types::Result;
+use crate::health::ComponentHealth;
+use crate::outputs::amqp::action::{AmqpAction, AmqpActionConfig, AmqpMessage};
+use crate::outputs::OutputTask;
+
+// Config
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct AmqpOutputConfig {
+    #[serde(flatten)]
+    pub connection: AmqpConnectionConfig,
+    #[serde(rename = "action")]
+    #[serde(default)]
+    pub actions: HashMap<ActionId, AmqpActionConfig>,
+}
+
+#[derive(Debug)]
+pub struct AmqpOutput {
+    id: ElId,
+    actions: Vec<AmqpAction>,
+    config: AmqpOutputConfig,
+    health: ComponentHealth,
+}
+
+impl AmqpOutput {
+    pub fn new(id: ElId, config: AmqpOutputConfig, health: ComponentHealth) -> Self {
+        let actions = config
+            .actions
+            .clone()
+            .into_iter()
+            .map(|(action_id, action_config)| AmqpAction::new(id.clone(), action_id, action_config))
+            .collect_vec();
+
+        Self {
+            id,
+            actions,
+            config,
+            health,
+        }
+    }
+
+    async fn connect(&self) -> Result<(Connection, Channel)> {
+        let conn = self
+            .config
+            .connection
+            .connect(format!("mqrt-output-{}", self.id))
+            .await?;
+        let channel = conn.create_channel().await?;
+        channel
+            .confirm_select(ConfirmSelectOptions::default())
+            .await?;
+
+        info!("{} connected to {}", self, self.config.connection.host());
+        Ok((conn, channel))
+    }
+}
+
+impl Display for AmqpOutput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "AmqpOutput[{}]", self.id)
+    }
+}
+
+#[async_trait]
+impl OutputTask for AmqpOutput {
+    async fn run(self: Box<Self>, mut chan: Receiver<ActionableEvent>) {
+        // Connected up front, and again on the next event after the connection is lost
+        let mut session: Option<(Connection, Channel)> = match self.connect().await {
+            Ok(connected) => {
+                self.health.set_connected(true);
+                Some(connected)
+            }
+            Err(err) => {
+                warn!("{} can not connect: {}", self, err);
+                None
+            }
+        };
+
+        while let Some(actionable_event) = chan.recv().await {
+            trace!("{} received {:?}", &self, actionable_event);
+
+            for action in &self.actions {
+                if action.action_id != actionable_event.action {
+                    continue;
+                }
+
+                trace!("{} will process the event", action);
+                let message = match action.process(&actionable_event).await {
+                    Some(message) => message,
+                    None => {
+                        trace!("{} skipped the event", action);
+                        continue;
+                    }
+                };
+
+                if !matches!(&session, Some((_, channel)) if channel.status().connected()) {
+                    match self.connect().await {
+                        Ok(connected) => {
+                            self.health.set_connected(true);
+                            session = Some(connected);
+                        }
+                        Err(err) => {
+                            warn!("{} can not connect: {}", self, err);
+                            self.health.set_connected(false);
+                            continue;
+                        }
+                    }
+                }
+
+                if let Some((_, channel)) = &session {
+                    match publish(channel, &message).await {
+                        Ok(()) => self.health.set_connected(true),
+                        Err(err) => {
+                            warn!("{} can not publish: {}", action, err);
+                            self.health.set_connected(channel.status().connected());
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Publishes the message and waits for the broker to confirm it
+async fn publish(channel: &Channel, message: &AmqpMessage) -> Result<()> {
+    let confirmation = channel
+        .basic_publish(
+            &message.exchange,
+            &message.routing_key,
+            BasicPublishOptions::default(),
+            &message.payload,
+            message.properties.clone(),
+        )
+        .await?
+        .await?;
+
+    if confirmation.is_nack() {
+        return Err(format!("the broker rejected {:?}", message.routing_key).into());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,79 @@
+use std::fmt::{Display, Formatter};
+
+use bytes::Bytes;
+use log::info;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::common::data::{ActionId, ActionableEvent, OutputId};
+use crate::common::template;
+use crate::outputs::payload::ActionPayloadConfig;
+
+// Config
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ExecActionConfig {
+    /// Program to run, it is not passed through a shell
+    command: String,
+    /// `{{ placeholder }}` templates, every item is passed as a single argument
+    #[serde(default)]
+    args: Vec<String>,
+    /// Pipe the payload to stdin of the command
+    #[serde(default)]
+    stdin: bool,
+    #[serde(default)]
+    payload: ActionPayloadConfig,
+}
+
+/// Command built by an action
+#[derive(Debug, Clone)]
+pub struct ExecActionCommand {
+    pub program: String,
+    pub args: Vec<String>,
+    pub stdin: Option<Bytes>,
+}
+
+// Action
+#[derive(Debug, Clone)]
+pub struct ExecAction {
+    output_id: OutputId,
+    pub action_id: ActionId,
+    config: ExecActionConfig,
+}
+
+impl Display for ExecAction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ExecAction[{}::{}]", self.output_id, self.action_id)
+    }
+}
+
+impl ExecAction {
+    pub fn new(output_id: OutputId, action_id: ActionId, config: ExecActionConfig) -> Self {
+        Self {
+            output_id,
+            action_id,
+            config,
+        }
+    }
+
+    pub async fn process(&self, event: &ActionableEvent) -> Option<ExecActionCommand> {
+        info!("Exec Action {} received {:?}", self.action_id, event);
+
+        let args = self
+            .config
+            .args
+            .iter()
+            .map(|arg| template::render(arg, &event.data))
+            .collect();
+        let stdin = match self.config.stdin {
+            true => Some(Bytes::from(self.config.payload.build(&event.data))),
+            false => None,
+        };
+
+        Some(ExecActionCommand {
+            program: self.config.command.clone(),
+            args,
+            stdin,
+        })
+    }
+}
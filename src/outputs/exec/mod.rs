@@ -0,0 +1,5 @@
+mod action;
+mod output;
+
+pub use output::ExecOutput;
+pub use output::ExecOutputConfig;
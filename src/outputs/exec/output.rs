@@ -0,0 +1,190 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::process::{Output, Stdio};
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use itertools::Itertools;
+use log::{debug, error, info, trace, warn};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use tokio::sync::mpsc::Receiver;
+use tokio::sync::Semaphore;
+
+use crate::common::data::{ActionId, ActionableEvent, ElId};
+use crate::common::types::Result;
+use crate::outputs::exec::action::{ExecAction, ExecActionCommand, ExecActionConfig};
+use crate::outputs::OutputTask;
+
+// Config
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ExecOutputConfig {
+    /// Commands are killed after the timeout
+    #[serde(default = "default_timeout_secs")]
+    timeout_secs: u64,
+    /// How many commands can run at the same time, the rest wait for their turn
+    #[serde(default = "default_max_concurrency")]
+    max_concurrency: usize,
+    working_dir: Option<String>,
+    /// Added to the environment of mqrt
+    #[serde(default)]
+    env: HashMap<String, String>,
+    /// Log stdout and stderr of every command
+    #[serde(default)]
+    log_output: bool,
+    #[serde(rename = "action")]
+    #[serde(default)]
+    pub actions: HashMap<ActionId, ExecActionConfig>,
+}
+
+fn default_timeout_secs() -> u64 {
+    30
+}
+
+fn default_max_concurrency() -> usize {
+    4
+}
+
+#[derive(Debug)]
+pub struct ExecOutput {
+    id: ElId,
+    actions: Vec<ExecAction>,
+    config: ExecOutputConfig,
+}
+
+impl ExecOutput {
+    pub fn new(id: ElId, config: ExecOutputConfig) -> Self {
+        let actions = config
+            .actions
+            .clone()
+            .into_iter()
+            .map(|(action_id, action_config)| ExecAction::new(id.clone(), action_id, action_config))
+            .collect_vec();
+
+        Self {
+            id,
+            actions,
+            config,
+        }
+    }
+}
+
+impl Display for ExecOutput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ExecOutput[{}]", self.id)
+    }
+}
+
+#[async_trait]
+impl OutputTask for ExecOutput {
+    async fn run(self: Box<Self>, mut chan: Receiver<ActionableEvent>) {
+        let permits = Arc::new(Semaphore::new(self.config.max_concurrency.max(1)));
+
+        while let Some(actionable_event) = chan.recv().await {
+            trace!("{} received {:?}", &self, actionable_event);
+
+            for action in self.actions.clone() {
+                if action.action_id != actionable_event.action {
+                    continue;
+                }
+
+                let permits = permits.clone();
+                let config = self.config.clone();
+                let actionable_event = actionable_event.clone();
+                tokio::spawn(async move {
+                    trace!("{} will process the event", action);
+                    let command = match action.process(&actionable_event).await {
+                        Some(command) => command,
+                        None => {
+                            trace!("{} skipped the event", action);
+                            return;
+                        }
+                    };
+
+                    let _permit = match permits.acquire().await {
+                        Ok(permit) => permit,
+                        Err(_) => return,
+                    };
+                    run_command(&config, &action, command).await;
+                });
+            }
+        }
+    }
+}
+
+async fn run_command(config: &ExecOutputConfig, action: &ExecAction, command: ExecActionCommand) {
+    let result = tokio::time::timeout(
+        Duration::from_secs(config.timeout_secs),
+        spawn(config, &command),
+    )
+    .await
+    .unwrap_or_else(|_| Err(format!("timed out after {}s", config.timeout_secs).into()));
+
+    let output = match result {
+        Ok(output) => output,
+        Err(err) => {
+            error!("{} `{}` failed: {}", action, command.program, err);
+            return;
+        }
+    };
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if config.log_output {
+        info!(
+            "{} `{}` stdout: {}, stderr: {}",
+            action,
+            command.program,
+            stdout.trim_end(),
+            stderr.trim_end()
+        );
+    }
+
+    match output.status.success() {
+        true => debug!("{} `{}` {}", action, command.program, output.status),
+        false => warn!(
+            "{} `{}` {}: {}",
+            action,
+            command.program,
+            output.status,
+            stderr.trim_end()
+        ),
+    }
+}
+
+async fn spawn(config: &ExecOutputConfig, command: &ExecActionCommand) -> Result<Output> {
+    let mut process = Command::new(&command.program);
+    process
+        .args(&command.args)
+        .envs(&config.env)
+        .stdin(match command.stdin {
+            Some(_) => Stdio::piped(),
+            None => Stdio::null(),
+        })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    if let Some(working_dir) = &config.working_dir {
+        process.current_dir(working_dir);
+    }
+
+    let mut child = process.spawn()?;
+    let stdin = child.stdin.take();
+    // Written while the output is read, a command may not take more input until its output is
+    // consumed; stdin is closed once written
+    let write_stdin = async move {
+        if let (Some(data), Some(mut stdin)) = (&command.stdin, stdin) {
+            // Commands that do not read stdin close it early, that is not an error
+            if let Err(err) = stdin.write_all(data).await {
+                trace!("Can not write stdin of `{}`: {}", command.program, err);
+            }
+        }
+    };
+
+    let (_, output) = tokio::join!(write_stdin, child.wait_with_output());
+    Ok(output?)
+}
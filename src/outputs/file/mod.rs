@@ -0,0 +1,5 @@
+mod output;
+mod writer;
+
+pub use output::FileOutput;
+pub use output::FileOutputConfig;
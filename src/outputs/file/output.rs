@@ -0,0 +1,98 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use itertools::Itertools;
+use log::{error, trace};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc::Receiver;
+
+use crate::common::data::{ActionId, ActionableEvent, ElId};
+use crate::outputs::file::writer::{RotatePeriod, RotatingFile};
+use crate::outputs::record::{RecordAction, RecordActionConfig};
+use crate::outputs::OutputTask;
+
+// Config
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct FileOutputConfig {
+    path: String,
+    /// Rotate the file once it grows over the size
+    max_size_mb: Option<u64>,
+    /// Rotate the file when an hour or a day is over
+    #[serde(default)]
+    rotate: RotatePeriod,
+    /// How many rotated files are kept, `0` keeps all of them
+    #[serde(default = "default_keep")]
+    keep: usize,
+    #[serde(rename = "action")]
+    #[serde(default)]
+    pub actions: HashMap<ActionId, RecordActionConfig>,
+}
+
+fn default_keep() -> usize {
+    5
+}
+
+#[derive(Debug)]
+pub struct FileOutput {
+    id: ElId,
+    actions: Vec<RecordAction>,
+    config: FileOutputConfig,
+}
+
+impl FileOutput {
+    pub fn new(id: ElId, config: FileOutputConfig) -> Self {
+        let actions = config
+            .actions
+            .clone()
+            .into_iter()
+            .map(|(action_id, action_config)| {
+                RecordAction::new(id.clone(), action_id, action_config)
+            })
+            .collect_vec();
+
+        Self {
+            id,
+            actions,
+            config,
+        }
+    }
+}
+
+impl Display for FileOutput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "FileOutput[{}]", self.id)
+    }
+}
+
+#[async_trait]
+impl OutputTask for FileOutput {
+    async fn run(self: Box<Self>, mut chan: Receiver<ActionableEvent>) {
+        let mut file = RotatingFile::new(
+            PathBuf::from(&self.config.path),
+            self.config.max_size_mb.map(|mb| mb * 1024 * 1024),
+            self.config.rotate,
+            self.config.keep,
+        );
+
+        // Events are written one by one to keep their order in the file
+        while let Some(actionable_event) = chan.recv().await {
+            trace!("{} received {:?}", &self, actionable_event);
+
+            for action in &self.actions {
+                if action.action_id != actionable_event.action {
+                    continue;
+                }
+
+                if let Some(record) = action.process(&actionable_event).await {
+                    file.write(&record).await.unwrap_or_else(|err| {
+                        error!("{} can not write to {}: {}", action, self.config.path, err)
+                    });
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,226 @@
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Local};
+use log::{debug, warn};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tokio::fs::{self, File, OpenOptions};
+use tokio::io::AsyncWriteExt;
+
+use crate::common::types::Result;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RotatePeriod {
+    Never,
+    Hourly,
+    Daily,
+}
+
+impl Default for RotatePeriod {
+    fn default() -> Self {
+        RotatePeriod::Never
+    }
+}
+
+impl RotatePeriod {
+    /// Files opened in different periods are rotated
+    fn period_of(&self, time: DateTime<Local>) -> Option<String> {
+        match self {
+            RotatePeriod::Never => None,
+            RotatePeriod::Hourly => Some(time.format("%Y%m%d%H").to_string()),
+            RotatePeriod::Daily => Some(time.format("%Y%m%d").to_string()),
+        }
+    }
+}
+
+/// Appends to a file, renaming it to `<path>.<timestamp>` once it is too big or too old
+#[derive(Debug)]
+pub struct RotatingFile {
+    path: PathBuf,
+    max_size: Option<u64>,
+    period: RotatePeriod,
+    keep: usize,
+    file: Option<File>,
+    size: u64,
+    opened: DateTime<Local>,
+}
+
+impl RotatingFile {
+    pub fn new(path: PathBuf, max_size: Option<u64>, period: RotatePeriod, keep: usize) -> Self {
+        Self {
+            path,
+            max_size,
+            period,
+            keep,
+            file: None,
+            size: 0,
+            opened: Local::now(),
+        }
+    }
+
+    pub async fn write(&mut self, record: &[u8]) -> Result<()> {
+        if self.file.is_none() {
+            self.open().await?;
+        }
+
+        if self.needs_rotation(record.len() as u64) {
+            self.rotate().await?;
+        }
+
+        let file = match self.file.as_mut() {
+            Some(file) => file,
+            None => self.open().await?,
+        };
+        file.write_all(record).await?;
+        file.flush().await?;
+        self.size += record.len() as u64;
+
+        Ok(())
+    }
+
+    async fn open(&mut self) -> Result<&mut File> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .await
+            .map_err(|err| format!("Can not open {}: {}", self.path.display(), err))?;
+        let metadata = file.metadata().await?;
+
+        self.size = metadata.len();
+        // An existing file belongs to the period it was last written in
+        self.opened = metadata
+            .modified()
+            .ok()
+            .filter(|_| metadata.len() > 0)
+            .map(DateTime::<Local>::from)
+            .unwrap_or_else(Local::now);
+
+        Ok(self.file.insert(file))
+    }
+
+    fn needs_rotation(&self, incoming: u64) -> bool {
+        if self.size == 0 {
+            return false;
+        }
+
+        let too_big = self
+            .max_size
+            .map(|max_size| self.size + incoming > max_size)
+            .unwrap_or(false);
+        let too_old = self.period.period_of(self.opened) != self.period.period_of(Local::now());
+
+        too_big || too_old
+    }
+
+    async fn rotate(&mut self) -> Result<()> {
+        self.file = None;
+
+        let mut rotated = suffixed(&self.path, &self.opened.format("%Y%m%d-%H%M%S").to_string());
+        let mut counter = 1;
+        while fs::metadata(&rotated).await.is_ok() {
+            rotated = suffixed(
+                &self.path,
+                &format!("{}-{}", self.opened.format("%Y%m%d-%H%M%S"), counter),
+            );
+            counter += 1;
+        }
+
+        debug!("Rotating {} to {}", self.path.display(), rotated.display());
+        fs::rename(&self.path, &rotated).await?;
+        self.open().await?;
+
+        if let Err(err) = self.remove_old().await {
+            warn!(
+                "Can not remove old files of {}: {}",
+                self.path.display(),
+                err
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Keeps `keep` newest rotated files, `0` keeps all of them
+    async fn remove_old(&self) -> Result<()> {
+        if self.keep == 0 {
+            return Ok(());
+        }
+
+        let dir = match self.path.parent() {
+            Some(dir) if dir != Path::new("") => dir.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+        let prefix = match self.path.file_name() {
+            Some(name) => format!("{}.", name.to_string_lossy()),
+            None => return Ok(()),
+        };
+
+        let mut rotated = Vec::new();
+        let mut entries = fs::read_dir(&dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let name = entry.file_name().to_string_lossy().to_string();
+            // Only the names `rotate` makes, e.g. `events.jsonl.20240101-120000` and `...-1`
+            let generated = name
+                .strip_prefix(&prefix)
+                .map(is_rotation_suffix)
+                .unwrap_or(false);
+            if generated {
+                let modified = entry.metadata().await?.modified()?;
+                rotated.push((modified, entry.path()));
+            }
+        }
+
+        rotated.sort();
+        let excess = rotated.len().saturating_sub(self.keep);
+        for (_, path) in rotated.into_iter().take(excess) {
+            debug!("Removing {}", path.display());
+            fs::remove_file(path).await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// `%Y%m%d-%H%M%S`, optionally followed by `-<counter>`
+fn is_rotation_suffix(suffix: &str) -> bool {
+    let digits = |x: &str| !x.is_empty() && x.bytes().all(|b| b.is_ascii_digit());
+
+    match suffix.split('-').collect::<Vec<_>>().as_slice() {
+        [date, time] => date.len() == 8 && time.len() == 6 && digits(date) && digits(time),
+        [date, time, counter] => {
+            date.len() == 8 && time.len() == 6 && digits(date) && digits(time) && digits(counter)
+        }
+        _ => false,
+    }
+}
+
+fn suffixed(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(".");
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_rotated_names_only() {
+        assert!(is_rotation_suffix("20240101-120000"));
+        assert!(is_rotation_suffix("20240101-120000-12"));
+
+        assert!(!is_rotation_suffix("bak"));
+        assert!(!is_rotation_suffix("jsonl"));
+        assert!(!is_rotation_suffix("20240101"));
+        assert!(!is_rotation_suffix("20240101-120000-"));
+        assert!(!is_rotation_suffix("20240101-120000.gz"));
+        assert!(!is_rotation_suffix("2024-01-01"));
+    }
+}
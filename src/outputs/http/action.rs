@@ -0,0 +1,95 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+
+use bytes::Bytes;
+use hyper::header::{HeaderName, HeaderValue};
+use hyper::{HeaderMap, Method, Uri};
+use log::{error, info};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::common::data::{ActionId, ActionableEvent, OutputId};
+use crate::common::template;
+use crate::common::types::Result;
+use crate::outputs::payload::ActionPayloadConfig;
+
+// Config
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct HttpActionConfig {
+    #[serde(default = "default_method")]
+    method: String,
+    /// `{{ placeholder }}` template, e.g. `http://host/api/{{ payload.id }}`
+    url: String,
+    /// Header values are templates as well
+    #[serde(default)]
+    headers: HashMap<String, String>,
+    /// Request body
+    #[serde(default)]
+    payload: ActionPayloadConfig,
+}
+
+fn default_method() -> String {
+    "POST".to_string()
+}
+
+/// Request built by an action, kept apart from `hyper::Request` so it can be retried
+#[derive(Debug, Clone)]
+pub struct HttpActionRequest {
+    pub method: Method,
+    pub uri: Uri,
+    pub headers: HeaderMap,
+    pub body: Bytes,
+}
+
+// Action
+#[derive(Debug, Clone)]
+pub struct HttpAction {
+    output_id: OutputId,
+    pub action_id: ActionId,
+    config: HttpActionConfig,
+}
+
+impl Display for HttpAction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "HttpAction[{}::{}]", self.output_id, self.action_id)
+    }
+}
+
+impl HttpAction {
+    pub fn new(output_id: OutputId, action_id: ActionId, config: HttpActionConfig) -> Self {
+        Self {
+            output_id,
+            action_id,
+            config,
+        }
+    }
+
+    pub async fn process(&self, event: &ActionableEvent) -> Option<HttpActionRequest> {
+        info!("Http Action {} received {:?}", self.action_id, event);
+
+        self.build_request(event)
+            .map_err(|err| error!("{} can not build request: {}", self, err))
+            .ok()
+    }
+
+    fn build_request(&self, event: &ActionableEvent) -> Result<HttpActionRequest> {
+        let method = Method::from_bytes(self.config.method.to_uppercase().as_bytes())?;
+        let uri: Uri = template::render(&self.config.url, &event.data).parse()?;
+
+        let mut headers = HeaderMap::new();
+        for (name, value) in &self.config.headers {
+            headers.insert(
+                HeaderName::from_bytes(name.as_bytes())?,
+                HeaderValue::from_str(&template::render(value, &event.data))?,
+            );
+        }
+
+        Ok(HttpActionRequest {
+            method,
+            uri,
+            headers,
+            body: Bytes::from(self.config.payload.build(&event.data)),
+        })
+    }
+}
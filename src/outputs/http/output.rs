@@ -0,0 +1,233 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use hyper::client::HttpConnector;
+use hyper::header::{HeaderValue, AUTHORIZATION};
+use hyper::{Body, Client, Request, StatusCode};
+use hyper_rustls::HttpsConnector;
+use itertools::Itertools;
+use log::{debug, error, info, trace, warn};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc::Receiver;
+
+use crate::common::data::{ActionId, ActionableEvent, ElId};
+use crate::common::secret::Secret;
+use crate::common::types::Result;
+use crate::outputs::http::action::{HttpAction, HttpActionConfig, HttpActionRequest};
+use crate::outputs::OutputTask;
+
+type HttpClient = Client<HttpsConnector<HttpConnector>>;
+
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+
+// Config
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct HttpOutputConfig {
+    /// Sent as `Authorization: Bearer <token>` with every request
+    token: Option<Secret>,
+    #[serde(default = "default_timeout_secs")]
+    timeout_secs: u64,
+    /// How many times a request is repeated on a connection error, timeout or 5xx response
+    #[serde(default = "default_retries")]
+    retries: u32,
+    /// Retry POST and PATCH requests too, they may be applied twice
+    #[serde(default)]
+    retry_non_idempotent: bool,
+    /// Delay before the first retry, doubled on every next one up to a minute
+    #[serde(default = "default_retry_delay_ms")]
+    retry_delay_ms: u64,
+    /// Log status and body of every response
+    #[serde(default)]
+    log_response: bool,
+    #[serde(rename = "action")]
+    #[serde(default)]
+    pub actions: HashMap<ActionId, HttpActionConfig>,
+}
+
+fn default_timeout_secs() -> u64 {
+    10
+}
+
+fn default_retries() -> u32 {
+    2
+}
+
+fn default_retry_delay_ms() -> u64 {
+    1000
+}
+
+#[derive(Debug)]
+pub struct HttpOutput {
+    id: ElId,
+    actions: Vec<HttpAction>,
+    config: HttpOutputConfig,
+}
+
+impl HttpOutput {
+    pub fn new(id: ElId, config: HttpOutputConfig) -> Self {
+        let actions = config
+            .actions
+            .clone()
+            .into_iter()
+            .map(|(action_id, action_config)| HttpAction::new(id.clone(), action_id, action_config))
+            .collect_vec();
+
+        Self {
+            id,
+            actions,
+            config,
+        }
+    }
+}
+
+impl Display for HttpOutput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "HttpOutput[{}]", self.id)
+    }
+}
+
+#[async_trait]
+impl OutputTask for HttpOutput {
+    async fn run(self: Box<Self>, mut chan: Receiver<ActionableEvent>) {
+        let https = hyper_rustls::HttpsConnectorBuilder::new()
+            .with_webpki_roots()
+            .https_or_http()
+            .enable_http1()
+            .build();
+        let client: HttpClient = Client::builder().build(https);
+
+        while let Some(actionable_event) = chan.recv().await {
+            trace!("{} received {:?}", &self, actionable_event);
+
+            for action in self.actions.clone() {
+                if action.action_id != actionable_event.action {
+                    continue;
+                }
+
+                let client = client.clone();
+                let config = self.config.clone();
+                let actionable_event = actionable_event.clone();
+                tokio::spawn(async move {
+                    trace!("{} will process the event", action);
+                    match action.process(&actionable_event).await {
+                        Some(request) => {
+                            send_with_retries(&client, &config, &action, request).await
+                        }
+                        None => trace!("{} skipped the event", action),
+                    }
+                });
+            }
+        }
+    }
+}
+
+async fn send_with_retries(
+    client: &HttpClient,
+    config: &HttpOutputConfig,
+    action: &HttpAction,
+    request: HttpActionRequest,
+) {
+    let mut delay = Duration::from_millis(config.retry_delay_ms);
+    let retries = if request.method.is_idempotent() || config.retry_non_idempotent {
+        config.retries
+    } else {
+        0
+    };
+
+    for attempt in 0..=retries {
+        if attempt > 0 {
+            tokio::time::sleep(delay).await;
+            delay = delay.saturating_mul(2).min(MAX_RETRY_DELAY);
+        }
+
+        let result = tokio::time::timeout(
+            Duration::from_secs(config.timeout_secs),
+            send(client, config, request.clone()),
+        )
+        .await
+        .unwrap_or_else(|_| Err("timed out".into()));
+
+        match result {
+            Ok((status, body)) => {
+                debug!(
+                    "{} {} {} -> {}",
+                    action, request.method, request.uri, status
+                );
+                if config.log_response {
+                    info!(
+                        "{} response {}: {}",
+                        action,
+                        status,
+                        String::from_utf8_lossy(&body)
+                    );
+                }
+
+                if status.is_server_error() {
+                    warn!(
+                        "{} {} {} failed with {} (attempt {}/{})",
+                        action,
+                        request.method,
+                        request.uri,
+                        status,
+                        attempt + 1,
+                        retries + 1
+                    );
+                    continue;
+                }
+                if !status.is_success() {
+                    warn!(
+                        "{} {} {} -> {}",
+                        action, request.method, request.uri, status
+                    );
+                }
+                return;
+            }
+            Err(err) => warn!(
+                "{} {} {} failed: {} (attempt {}/{})",
+                action,
+                request.method,
+                request.uri,
+                err,
+                attempt + 1,
+                retries + 1
+            ),
+        }
+    }
+
+    error!(
+        "{} gave up on {} {} after {} attempts",
+        action,
+        request.method,
+        request.uri,
+        retries + 1
+    );
+}
+
+async fn send(
+    client: &HttpClient,
+    config: &HttpOutputConfig,
+    request: HttpActionRequest,
+) -> Result<(StatusCode, bytes::Bytes)> {
+    let mut builder = Request::builder().method(request.method).uri(request.uri);
+    if let Some(headers) = builder.headers_mut() {
+        headers.extend(request.headers);
+        if let Some(token) = &config.token {
+            headers.insert(
+                AUTHORIZATION,
+                HeaderValue::from_str(&format!("Bearer {}", token.expose()))?,
+            );
+        }
+    }
+
+    let response = client
+        .request(builder.body(Body::from(request.body))?)
+        .await?;
+    let status = response.status();
+    let body = hyper::body::to_bytes(response.into_body()).await?;
+
+    Ok((status, body))
+}
@@ -0,0 +1,61 @@
+use std::fmt::{Display, Formatter};
+
+use bytes::Bytes;
+use log::info;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::common::data::{ActionId, ActionableEvent, DataEventMeta, OutputId};
+use crate::common::template;
+use crate::coordinator::BusEvent;
+use crate::outputs::payload::ActionPayloadConfig;
+
+// Config
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct InternalActionConfig {
+    /// `{{ placeholder }}` template, e.g. `rooms/{{ payload.room }}`
+    channel: String,
+    #[serde(default)]
+    payload: ActionPayloadConfig,
+}
+
+// Action
+#[derive(Debug, Clone)]
+pub struct InternalAction {
+    output_id: OutputId,
+    pub action_id: ActionId,
+    config: InternalActionConfig,
+}
+
+impl Display for InternalAction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "InternalAction[{}::{}]", self.output_id, self.action_id)
+    }
+}
+
+impl InternalAction {
+    pub fn new(output_id: OutputId, action_id: ActionId, config: InternalActionConfig) -> Self {
+        Self {
+            output_id,
+            action_id,
+            config,
+        }
+    }
+
+    pub async fn process(&self, event: &ActionableEvent) -> Option<BusEvent> {
+        info!("Internal Action {} received {:?}", self.action_id, event);
+
+        // Events coming from the bus are one hop deeper on the way back
+        let depth = match &event.data.meta {
+            DataEventMeta::Internal { depth, .. } => depth + 1,
+            _ => 1,
+        };
+
+        Some(BusEvent {
+            channel: template::render(&self.config.channel, &event.data),
+            depth,
+            payload: Bytes::from(self.config.payload.build(&event.data)),
+        })
+    }
+}
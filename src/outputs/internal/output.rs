@@ -0,0 +1,89 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+
+use async_trait::async_trait;
+use itertools::Itertools;
+use log::{trace, warn};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc::Receiver;
+
+use crate::common::data::{ActionId, ActionableEvent, ElId};
+use crate::coordinator::InternalBus;
+use crate::outputs::internal::action::{InternalAction, InternalActionConfig};
+use crate::outputs::OutputTask;
+
+// Config
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct InternalOutputConfig {
+    /// Events that went through the bus more times are dropped, it stops cycles between rules
+    #[serde(default = "default_max_depth")]
+    max_depth: u32,
+    #[serde(rename = "action")]
+    #[serde(default)]
+    pub actions: HashMap<ActionId, InternalActionConfig>,
+}
+
+fn default_max_depth() -> u32 {
+    8
+}
+
+#[derive(Debug)]
+pub struct InternalOutput {
+    id: ElId,
+    actions: Vec<InternalAction>,
+    config: InternalOutputConfig,
+    bus: InternalBus,
+}
+
+impl InternalOutput {
+    pub fn new(id: ElId, config: InternalOutputConfig, bus: InternalBus) -> Self {
+        let actions = config
+            .actions
+            .clone()
+            .into_iter()
+            .map(|(action_id, action_config)| {
+                InternalAction::new(id.clone(), action_id, action_config)
+            })
+            .collect_vec();
+
+        Self {
+            id,
+            actions,
+            config,
+            bus,
+        }
+    }
+}
+
+impl Display for InternalOutput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "InternalOutput[{}]", self.id)
+    }
+}
+
+#[async_trait]
+impl OutputTask for InternalOutput {
+    async fn run(self: Box<Self>, mut chan: Receiver<ActionableEvent>) {
+        while let Some(actionable_event) = chan.recv().await {
+            trace!("{} received {:?}", &self, actionable_event);
+
+            for action in &self.actions {
+                if action.action_id != actionable_event.action {
+                    continue;
+                }
+
+                trace!("{} will process the event", action);
+                match action.process(&actionable_event).await {
+                    Some(event) if event.depth > self.config.max_depth => warn!(
+                        "{} dropped an event for {:?} after {} internal hops, rules are probably in a cycle",
+                        action, event.channel, self.config.max_depth
+                    ),
+                    Some(event) => self.bus.publish(event),
+                    None => trace!("{} skipped the event", action),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+
+use log::info;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::common::data::{ActionId, ActionableEvent, OutputId};
+use crate::common::template;
+use crate::outputs::payload::ActionPayloadConfig;
+
+// Config
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct KafkaActionConfig {
+    /// `{{ placeholder }}` template, e.g. `devices.{{ payload.type }}`
+    topic: String,
+    /// Template as well, records without a key are spread over all partitions
+    key: Option<String>,
+    /// Header values are templates, e.g. `{ "mqtt-topic" = "{{ topic }}" }`
+    #[serde(default)]
+    headers: HashMap<String, String>,
+    #[serde(default)]
+    payload: ActionPayloadConfig,
+}
+
+/// Record built by an action, ready to be produced
+#[derive(Debug, Clone)]
+pub struct KafkaRecord {
+    pub topic: String,
+    pub key: Option<String>,
+    pub headers: Vec<(String, String)>,
+    pub payload: Vec<u8>,
+}
+
+// Action
+#[derive(Debug, Clone)]
+pub struct KafkaAction {
+    output_id: OutputId,
+    pub action_id: ActionId,
+    config: KafkaActionConfig,
+}
+
+impl Display for KafkaAction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "KafkaAction[{}::{}]", self.output_id, self.action_id)
+    }
+}
+
+impl KafkaAction {
+    pub fn new(output_id: OutputId, action_id: ActionId, config: KafkaActionConfig) -> Self {
+        Self {
+            output_id,
+            action_id,
+            config,
+        }
+    }
+
+    pub async fn process(&self, event: &ActionableEvent) -> Option<KafkaRecord> {
+        info!("Kafka Action {} received {:?}", self.action_id, event);
+
+        Some(KafkaRecord {
+            topic: template::render(&self.config.topic, &event.data),
+            key: self
+                .config
+                .key
+                .as_ref()
+                .map(|key| template::render(key, &event.data)),
+            headers: self
+                .config
+                .headers
+                .iter()
+                .map(|(name, value)| (name.clone(), template::render(value, &event.data)))
+                .collect(),
+            payload: self.config.payload.build(&event.data),
+        })
+    }
+}
@@ -0,0 +1,187 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+
+use async_trait::async_trait;
+use itertools::Itertools;
+use log::{debug, error, info, trace, warn};
+use rdkafka::message::OwnedHeaders;
+use rdkafka::producer::{FutureProducer, FutureRecord};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc::Receiver;
+
+use crate::common::data::{ActionId, ActionableEvent, ElId};
+use crate::common::kafka::KafkaConnectionConfig;
+use crate::common::types::Result;
+use crate::health::ComponentHealth;
+use crate::outputs::kafka::action::{KafkaAction, KafkaActionConfig, KafkaRecord};
+use crate::outputs::OutputTask;
+
+// Config
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct KafkaOutputConfig {
+    #[serde(flatten)]
+    pub connection: KafkaConnectionConfig,
+    /// `all`, `1` or `0` acknowledgements required from the brokers
+    #[serde(default = "default_acks")]
+    acks: String,
+    /// How long records are collected into a batch before sending it
+    #[serde(default = "default_linger_ms")]
+    linger_ms: u64,
+    /// Maximum number of records in a batch
+    #[serde(default = "default_batch_size")]
+    batch_size: usize,
+    /// Records not delivered in time are reported as failed
+    #[serde(default = "default_timeout_secs")]
+    timeout_secs: u64,
+    #[serde(rename = "action")]
+    #[serde(default)]
+    pub actions: HashMap<ActionId, KafkaActionConfig>,
+}
+
+fn default_acks() -> String {
+    "all".to_string()
+}
+
+fn default_linger_ms() -> u64 {
+    5
+}
+
+fn default_batch_size() -> usize {
+    10000
+}
+
+fn default_timeout_secs() -> u64 {
+    30
+}
+
+#[derive(Debug)]
+pub struct KafkaOutput {
+    id: ElId,
+    actions: Vec<KafkaAction>,
+    config: KafkaOutputConfig,
+    health: ComponentHealth,
+}
+
+impl KafkaOutput {
+    pub fn new(id: ElId, config: KafkaOutputConfig, health: ComponentHealth) -> Self {
+        let actions = config
+            .actions
+            .clone()
+            .into_iter()
+            .map(|(action_id, action_config)| {
+                KafkaAction::new(id.clone(), action_id, action_config)
+            })
+            .collect_vec();
+
+        Self {
+            id,
+            actions,
+            config,
+            health,
+        }
+    }
+
+    fn create_producer(&self) -> Result<FutureProducer> {
+        Ok(self
+            .config
+            .connection
+            .client_config()
+            .set("acks", &self.config.acks)
+            .set("linger.ms", self.config.linger_ms.to_string())
+            .set("batch.num.messages", self.config.batch_size.to_string())
+            .set(
+                "message.timeout.ms",
+                (self.config.timeout_secs * 1000).to_string(),
+            )
+            .create()?)
+    }
+}
+
+impl Display for KafkaOutput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "KafkaOutput[{}]", self.id)
+    }
+}
+
+#[async_trait]
+impl OutputTask for KafkaOutput {
+    async fn run(self: Box<Self>, mut chan: Receiver<ActionableEvent>) {
+        let producer = match self.create_producer() {
+            Ok(producer) => producer,
+            Err(err) => {
+                error!("{} can not create a producer: {}", self, err);
+                return;
+            }
+        };
+        info!("{} producing to {}", self, self.config.connection.brokers());
+        self.health.set_connected(true);
+
+        while let Some(actionable_event) = chan.recv().await {
+            trace!("{} received {:?}", &self, actionable_event);
+
+            for action in &self.actions {
+                if action.action_id != actionable_event.action {
+                    continue;
+                }
+
+                trace!("{} will process the event", action);
+                match action.process(&actionable_event).await {
+                    Some(record) => produce(&producer, action, record, &self.health),
+                    None => trace!("{} skipped the event", action),
+                }
+            }
+        }
+    }
+}
+
+/// Queues the record for the next batch, the delivery report is awaited in background
+fn produce(
+    producer: &FutureProducer,
+    action: &KafkaAction,
+    record: KafkaRecord,
+    health: &ComponentHealth,
+) {
+    let headers = record
+        .headers
+        .iter()
+        .fold(OwnedHeaders::new(), |headers, (name, value)| {
+            headers.add(name, value)
+        });
+    let mut future_record = FutureRecord::to(&record.topic)
+        .payload(&record.payload)
+        .headers(headers);
+    if let Some(key) = &record.key {
+        future_record = future_record.key(key);
+    }
+
+    let delivery = match producer.send_result(future_record) {
+        Ok(delivery) => delivery,
+        Err((err, _)) => {
+            warn!(
+                "{} can not queue a record to {}: {}",
+                action, record.topic, err
+            );
+            return;
+        }
+    };
+
+    let action = action.to_string();
+    let health = health.clone();
+    tokio::spawn(async move {
+        match delivery.await {
+            Ok(Ok((partition, offset))) => {
+                debug!(
+                    "{} delivered to {}[{}]@{}",
+                    action, record.topic, partition, offset
+                );
+                health.set_connected(true);
+            }
+            Ok(Err((err, _))) => {
+                warn!("{} can not deliver to {}: {}", action, record.topic, err);
+                health.set_connected(false);
+            }
+            Err(_) => warn!("{} lost the delivery report for {}", action, record.topic),
+        }
+    });
+}
@@ -1,4 +1,17 @@
+pub mod amqp;
+pub mod exec;
+pub mod file;
+pub mod http;
+pub mod internal;
+#[cfg(feature = "kafka")]
+pub mod kafka;
 pub mod mqtt;
+pub mod payload;
+pub mod record;
+pub mod redis;
+pub mod sqlite;
+pub mod stdout;
+pub mod websocket;
 
 use crate::common::data::ActionableEvent;
 use async_trait::async_trait;
@@ -1,33 +1,18 @@
 use crate::common::data::{ActionId, ActionableEvent, OutputId};
-use crate::common::types::Result;
-use log::{error, info};
+use crate::outputs::payload::ActionPayloadConfig;
+use log::info;
 use paho_mqtt;
 use paho_mqtt::Message;
-use rquickjs as rjs;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::fmt::{Display, Formatter};
 
 // Config
-#[derive(Debug, Default, PartialEq, Serialize, Deserialize, Clone)]
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize, Clone, JsonSchema)]
 pub struct MqttActionConfig {
     topic: String,
     #[serde(default)]
-    payload: MqttActionPayloadConfig,
-}
-
-#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
-#[serde(tag = "type", rename_all = "snake_case")]
-enum MqttActionPayloadConfig {
-    Passthrough,
-    Drop,
-    Static { data: String },
-    Js { code: String },
-}
-
-impl Default for MqttActionPayloadConfig {
-    fn default() -> Self {
-        MqttActionPayloadConfig::Passthrough
-    }
+    payload: ActionPayloadConfig,
 }
 
 // Action
@@ -57,34 +42,9 @@ impl MqttAction {
         info!("Mqtt Action {} received {:?}", self.action_id, event);
 
         let topic = self.config.topic.clone();
-        let payload = match &self.config.payload {
-            MqttActionPayloadConfig::Passthrough => event.data.payload.to_vec(),
-            MqttActionPayloadConfig::Drop => Vec::new(),
-            MqttActionPayloadConfig::Static { data } => data.as_bytes().to_vec(),
-            MqttActionPayloadConfig::Js { code } => process_js(code, event.data.payload.to_vec())
-                .unwrap_or_else(|err| {
-                    error!("Can not process javascript code={}: {:?}", code, err);
-                    Vec::new()
-                }),
-        };
+        let payload = self.config.payload.build(&event.data);
 
         let message = paho_mqtt::Message::new(topic, payload, paho_mqtt::QOS_1);
         Some(message)
     }
 }
-
-fn process_js(code: &str, payload: Vec<u8>) -> Result<Vec<u8>> {
-    let rt = rjs::Runtime::new().unwrap();
-    let ctx = rjs::Context::full(&rt).unwrap();
-
-    let result: Result<String> = ctx.with(|ctx| {
-        let func: rjs::Function = ctx.eval(format!("(payload) => {{ {} }}", code))?;
-
-        let payload_string: String = String::from_utf8(payload)?;
-
-        let result = func.call((payload_string,))?;
-        Ok(result)
-    });
-
-    result.map(Vec::from)
-}
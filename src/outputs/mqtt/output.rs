@@ -1,42 +1,65 @@
-use paho_mqtt;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
-use std::time::Duration;
 
-use crate::common::data::{ActionId, ActionableEvent, ElId};
-use crate::common::utils::random_alphanumeric;
+use crate::common::data::{ActionId, ActionableEvent, ConnectionId, ElId};
+use crate::common::mqtt::{MqttConnection, MqttConnectionConfig};
+use crate::common::types::Result;
+use crate::health::ComponentHealth;
 use crate::outputs::mqtt::action::{MqttAction, MqttActionConfig};
 use crate::outputs::OutputTask;
 use async_trait::async_trait;
 
 use itertools::Itertools;
 use log::{error, trace};
-use paho_mqtt::{ConnectOptions, Message};
+use paho_mqtt::Message;
 use tokio::sync::mpsc::{channel, Receiver};
 use tokio_stream::StreamExt;
 
 // Config
-#[derive(Debug, Default, PartialEq, Serialize, Deserialize, Clone)]
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize, Clone, JsonSchema)]
 pub struct MqttOutputConfig {
-    host: String,
-    port: u16,
-    username: Option<String>,
-    password: Option<String>,
+    /// Id of a shared `[connection.<id>]` to use instead of own connection settings
+    pub connection: Option<ConnectionId>,
+    #[serde(flatten)]
+    pub settings: MqttConnectionConfig,
     #[serde(rename = "action")]
     #[serde(default)]
-    actions: HashMap<ActionId, MqttActionConfig>,
+    pub actions: HashMap<ActionId, MqttActionConfig>,
+}
+
+impl MqttOutputConfig {
+    pub fn resolve_secrets(&mut self) -> Result<()> {
+        self.settings.resolve_secrets()
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        match self.connection {
+            Some(_) if !self.settings.is_empty() => {
+                Err("Connection settings can not be set along with `connection`".into())
+            }
+            Some(_) => Ok(()),
+            None => self.settings.validate(),
+        }
+    }
 }
 
 #[derive(Debug)]
 pub struct MqttOutput {
     id: ElId,
     actions: Vec<MqttAction>,
-    config: MqttOutputConfig,
+    health: ComponentHealth,
+    connection: MqttConnection,
 }
 
 impl MqttOutput {
-    pub fn new(id: ElId, config: MqttOutputConfig) -> Self {
+    pub fn new(
+        id: ElId,
+        config: MqttOutputConfig,
+        health: ComponentHealth,
+        connection: MqttConnection,
+    ) -> Self {
         let actions = config
             .actions
             .clone()
@@ -47,7 +70,8 @@ impl MqttOutput {
         Self {
             id,
             actions,
-            config,
+            health,
+            connection,
         }
     }
 }
@@ -63,7 +87,11 @@ impl OutputTask for MqttOutput {
     async fn run(self: Box<Self>, mut chan: Receiver<ActionableEvent>) {
         let (tx, rx) = channel(128);
         {
-            let writer = Box::new(MqttOutputWriter::new(self.id.clone(), self.config.clone()));
+            let writer = Box::new(MqttOutputWriter::new(
+                self.id.clone(),
+                self.health.clone(),
+                self.connection.clone(),
+            ));
             tokio::spawn(async move {
                 writer.run(rx).await;
             });
@@ -100,56 +128,34 @@ impl OutputTask for MqttOutput {
 // Writer
 pub struct MqttOutputWriter {
     id: ElId,
-    config: MqttOutputConfig,
+    health: ComponentHealth,
+    connection: MqttConnection,
 }
 
 impl MqttOutputWriter {
-    pub fn new(id: ElId, config: MqttOutputConfig) -> Self {
-        Self { id, config }
-    }
-
-    pub fn get_connect_options(&self) -> ConnectOptions {
-        let mut connect_opts = paho_mqtt::ConnectOptionsBuilder::new();
-        connect_opts
-            .keep_alive_interval(Duration::from_secs(30))
-            .automatic_reconnect(Duration::from_secs(1), Duration::from_secs(16))
-            .mqtt_version(paho_mqtt::MQTT_VERSION_3_1_1)
-            .clean_session(true);
-
-        if let Some(username) = &self.config.username {
-            connect_opts.user_name(username);
-        }
-
-        if let Some(password) = &self.config.password {
-            connect_opts.password(password);
+    pub fn new(id: ElId, health: ComponentHealth, connection: MqttConnection) -> Self {
+        Self {
+            id,
+            health,
+            connection,
         }
-
-        connect_opts.finalize()
     }
 
     async fn run(self: Box<Self>, mut chan: Receiver<Message>) {
-        let host = format!("tcp://{}:{}", self.config.host, self.config.port);
-
-        let create_opts = paho_mqtt::CreateOptionsBuilder::new()
-            .server_uri(host)
-            .client_id(format!("mqrt-output-{}-{}", self.id, random_alphanumeric()))
-            .finalize();
+        self.connection.register(self.health.clone());
+        self.connection.start();
 
-        let cli = paho_mqtt::AsyncClient::new(create_opts).unwrap_or_else(|e| {
-            error!("Error creating the client: {:?}", e);
-            panic!("Can not create MQTT client")
-        });
-
-        trace!("Connecting to the MQTT server...");
-        cli.connect(self.get_connect_options())
-            .await
-            .expect("Can not connect to MQTT");
+        trace!("{} waiting for {} to connect...", self, self.connection);
+        self.connection.wait_connected().await;
 
         while let Some(message) = chan.recv().await {
             trace!("{} received {:?}", self, message);
-            cli.publish(message).await.unwrap_or_else(|err| {
-                error!("Can not send message to Mqtt: {:?}", err);
-            });
+            self.connection
+                .publish(message)
+                .await
+                .unwrap_or_else(|err| {
+                    error!("Can not send message to Mqtt: {:?}", err);
+                });
         }
     }
 }
@@ -0,0 +1,66 @@
+use log::error;
+use rquickjs as rjs;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::common::data::DataEvent;
+use crate::common::template;
+use crate::common::types::Result;
+
+/// How an action builds the outgoing payload from the event
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ActionPayloadConfig {
+    Passthrough,
+    Drop,
+    Static {
+        data: String,
+    },
+    Js {
+        code: String,
+    },
+    /// `{{ placeholder }}` template, see `common::template`
+    Template {
+        template: String,
+    },
+}
+
+impl Default for ActionPayloadConfig {
+    fn default() -> Self {
+        ActionPayloadConfig::Passthrough
+    }
+}
+
+impl ActionPayloadConfig {
+    pub fn build(&self, data: &DataEvent) -> Vec<u8> {
+        match self {
+            ActionPayloadConfig::Passthrough => data.payload.to_vec(),
+            ActionPayloadConfig::Drop => Vec::new(),
+            ActionPayloadConfig::Static { data } => data.as_bytes().to_vec(),
+            ActionPayloadConfig::Js { code } => process_js(code, data.payload.to_vec())
+                .unwrap_or_else(|err| {
+                    error!("Can not process javascript code={}: {:?}", code, err);
+                    Vec::new()
+                }),
+            ActionPayloadConfig::Template { template } => {
+                template::render(template, data).into_bytes()
+            }
+        }
+    }
+}
+
+fn process_js(code: &str, payload: Vec<u8>) -> Result<Vec<u8>> {
+    let rt = rjs::Runtime::new().unwrap();
+    let ctx = rjs::Context::full(&rt).unwrap();
+
+    let result: Result<String> = ctx.with(|ctx| {
+        let func: rjs::Function = ctx.eval(format!("(payload) => {{ {} }}", code))?;
+
+        let payload_string: String = String::from_utf8(payload)?;
+
+        let result = func.call((payload_string,))?;
+        Ok(result)
+    });
+
+    result.map(Vec::from)
+}
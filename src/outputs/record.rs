@@ -0,0 +1,96 @@
+use std::fmt::{Display, Formatter};
+
+use chrono::{Local, SecondsFormat};
+use log::info;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::common::data::{ActionId, ActionableEvent, OutputId};
+use crate::outputs::payload::ActionPayloadConfig;
+
+// Config
+/// Action of the `file` and `stdout` outputs
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct RecordActionConfig {
+    #[serde(default)]
+    format: RecordFormat,
+    #[serde(default)]
+    payload: ActionPayloadConfig,
+}
+
+/// How `file` and `stdout` outputs write an event
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordFormat {
+    /// JSON line with `time`, `output`, `action`, `meta` and `payload`
+    Json,
+    /// Payload as is, followed by a newline
+    Raw,
+}
+
+impl Default for RecordFormat {
+    fn default() -> Self {
+        RecordFormat::Json
+    }
+}
+
+impl RecordFormat {
+    pub fn format(&self, event: &ActionableEvent, payload: &[u8]) -> Vec<u8> {
+        let mut record = match self {
+            RecordFormat::Json => {
+                // JSON payloads are embedded as is, anything else as a string
+                let payload = serde_json::from_slice(payload).unwrap_or_else(|_| {
+                    Value::String(String::from_utf8_lossy(payload).to_string())
+                });
+                json!({
+                    "time": Local::now().to_rfc3339_opts(SecondsFormat::Millis, false),
+                    "output": event.output.to_string(),
+                    "action": event.action.to_string(),
+                    "meta": event.data.meta,
+                    "payload": payload,
+                })
+                .to_string()
+                .into_bytes()
+            }
+            RecordFormat::Raw => payload.to_vec(),
+        };
+
+        if record.last() != Some(&b'\n') {
+            record.push(b'\n');
+        }
+        record
+    }
+}
+
+// Action
+#[derive(Debug, Clone)]
+pub struct RecordAction {
+    output_id: OutputId,
+    pub action_id: ActionId,
+    config: RecordActionConfig,
+}
+
+impl Display for RecordAction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "RecordAction[{}::{}]", self.output_id, self.action_id)
+    }
+}
+
+impl RecordAction {
+    pub fn new(output_id: OutputId, action_id: ActionId, config: RecordActionConfig) -> Self {
+        Self {
+            output_id,
+            action_id,
+            config,
+        }
+    }
+
+    pub async fn process(&self, event: &ActionableEvent) -> Option<Vec<u8>> {
+        info!("Record Action {} received {:?}", self.action_id, event);
+
+        let payload = self.config.payload.build(&event.data);
+        Some(self.config.format.format(event, &payload))
+    }
+}
@@ -0,0 +1,117 @@
+use std::fmt::{Display, Formatter};
+
+use log::info;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::common::data::{ActionId, ActionableEvent, OutputId};
+use crate::common::template;
+use crate::outputs::payload::ActionPayloadConfig;
+
+// Config
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct RedisActionConfig {
+    command: RedisCommandConfig,
+    #[serde(default)]
+    payload: ActionPayloadConfig,
+}
+
+/// Channel, stream and key names are `{{ placeholder }}` templates
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum RedisCommandConfig {
+    Publish {
+        channel: String,
+    },
+    Xadd {
+        stream: String,
+        /// Entry field holding the payload
+        #[serde(default = "default_field")]
+        field: String,
+        /// Approximate stream length kept with `MAXLEN ~`
+        maxlen: Option<usize>,
+    },
+    Set {
+        key: String,
+        /// Key expiration, the key is kept forever if not set
+        ttl_secs: Option<usize>,
+    },
+}
+
+fn default_field() -> String {
+    "payload".to_string()
+}
+
+/// Command built by an action, with its templates rendered
+#[derive(Debug, Clone)]
+pub enum RedisCommand {
+    Publish {
+        channel: String,
+        payload: Vec<u8>,
+    },
+    Xadd {
+        stream: String,
+        field: String,
+        maxlen: Option<usize>,
+        payload: Vec<u8>,
+    },
+    Set {
+        key: String,
+        ttl_secs: Option<usize>,
+        payload: Vec<u8>,
+    },
+}
+
+// Action
+#[derive(Debug, Clone)]
+pub struct RedisAction {
+    output_id: OutputId,
+    pub action_id: ActionId,
+    config: RedisActionConfig,
+}
+
+impl Display for RedisAction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "RedisAction[{}::{}]", self.output_id, self.action_id)
+    }
+}
+
+impl RedisAction {
+    pub fn new(output_id: OutputId, action_id: ActionId, config: RedisActionConfig) -> Self {
+        Self {
+            output_id,
+            action_id,
+            config,
+        }
+    }
+
+    pub async fn process(&self, event: &ActionableEvent) -> Option<RedisCommand> {
+        info!("Redis Action {} received {:?}", self.action_id, event);
+
+        let payload = self.config.payload.build(&event.data);
+        let command = match &self.config.command {
+            RedisCommandConfig::Publish { channel } => RedisCommand::Publish {
+                channel: template::render(channel, &event.data),
+                payload,
+            },
+            RedisCommandConfig::Xadd {
+                stream,
+                field,
+                maxlen,
+            } => RedisCommand::Xadd {
+                stream: template::render(stream, &event.data),
+                field: field.clone(),
+                maxlen: *maxlen,
+                payload,
+            },
+            RedisCommandConfig::Set { key, ttl_secs } => RedisCommand::Set {
+                key: template::render(key, &event.data),
+                ttl_secs: *ttl_secs,
+                payload,
+            },
+        };
+
+        Some(command)
+    }
+}
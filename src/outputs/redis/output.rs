@@ -0,0 +1,155 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use itertools::Itertools;
+use log::{debug, trace, warn};
+use redis::aio::ConnectionManager;
+use redis::streams::StreamMaxlen;
+use redis::{AsyncCommands, RedisResult};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc::Receiver;
+
+use crate::common::data::{ActionId, ActionableEvent, ElId};
+use crate::common::redis::RedisConnectionConfig;
+use crate::common::types::Result;
+use crate::health::ComponentHealth;
+use crate::outputs::redis::action::{RedisAction, RedisActionConfig, RedisCommand};
+use crate::outputs::OutputTask;
+
+const RETRY_INTERVAL: Duration = Duration::from_secs(5);
+
+// Config
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct RedisOutputConfig {
+    #[serde(flatten)]
+    pub connection: RedisConnectionConfig,
+    #[serde(rename = "action")]
+    #[serde(default)]
+    pub actions: HashMap<ActionId, RedisActionConfig>,
+}
+
+#[derive(Debug)]
+pub struct RedisOutput {
+    id: ElId,
+    actions: Vec<RedisAction>,
+    config: RedisOutputConfig,
+    health: ComponentHealth,
+}
+
+impl RedisOutput {
+    pub fn new(id: ElId, config: RedisOutputConfig, health: ComponentHealth) -> Self {
+        let actions = config
+            .actions
+            .clone()
+            .into_iter()
+            .map(|(action_id, action_config)| {
+                RedisAction::new(id.clone(), action_id, action_config)
+            })
+            .collect_vec();
+
+        Self {
+            id,
+            actions,
+            config,
+            health,
+        }
+    }
+
+    /// Waits for the first connection, later reconnects are done by the manager itself
+    async fn connect(&self) -> Result<ConnectionManager> {
+        let client = self.config.connection.client()?;
+        loop {
+            match client.get_tokio_connection_manager().await {
+                Ok(conn) => return Ok(conn),
+                Err(err) => {
+                    warn!("{} {}, retrying in {:?}", self, err, RETRY_INTERVAL);
+                    tokio::time::sleep(RETRY_INTERVAL).await;
+                }
+            }
+        }
+    }
+}
+
+impl Display for RedisOutput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "RedisOutput[{}]", self.id)
+    }
+}
+
+#[async_trait]
+impl OutputTask for RedisOutput {
+    async fn run(self: Box<Self>, mut chan: Receiver<ActionableEvent>) {
+        let conn = match self.connect().await {
+            Ok(conn) => conn,
+            Err(err) => {
+                warn!("{} has an invalid connection config: {}", self, err);
+                return;
+            }
+        };
+        debug!("{} connected to {}", self, self.config.connection.url());
+        self.health.set_connected(true);
+
+        while let Some(actionable_event) = chan.recv().await {
+            trace!("{} received {:?}", &self, actionable_event);
+
+            for action in &self.actions {
+                if action.action_id != actionable_event.action {
+                    continue;
+                }
+
+                trace!("{} will process the event", action);
+                match action.process(&actionable_event).await {
+                    Some(command) => match send(conn.clone(), command).await {
+                        Ok(()) => self.health.set_connected(true),
+                        Err(err) => {
+                            warn!("{} command failed: {}", action, err);
+                            if err.is_io_error() || err.is_connection_dropped() {
+                                self.health.set_connected(false);
+                            }
+                        }
+                    },
+                    None => trace!("{} skipped the event", action),
+                }
+            }
+        }
+    }
+}
+
+async fn send(mut conn: ConnectionManager, command: RedisCommand) -> RedisResult<()> {
+    match command {
+        RedisCommand::Publish { channel, payload } => conn.publish(channel, payload).await,
+        RedisCommand::Xadd {
+            stream,
+            field,
+            maxlen: Some(maxlen),
+            payload,
+        } => {
+            conn.xadd_maxlen(
+                stream,
+                StreamMaxlen::Approx(maxlen),
+                "*",
+                &[(field, payload)],
+            )
+            .await
+        }
+        RedisCommand::Xadd {
+            stream,
+            field,
+            maxlen: None,
+            payload,
+        } => conn.xadd(stream, "*", &[(field, payload)]).await,
+        RedisCommand::Set {
+            key,
+            ttl_secs: Some(ttl_secs),
+            payload,
+        } => conn.set_ex(key, payload, ttl_secs).await,
+        RedisCommand::Set {
+            key,
+            ttl_secs: None,
+            payload,
+        } => conn.set(key, payload).await,
+    }
+}
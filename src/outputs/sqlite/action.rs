@@ -0,0 +1,141 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use itertools::Itertools;
+use log::info;
+use rusqlite::types::Value as SqlValue;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::common::data::{ActionId, ActionableEvent, DataEvent, OutputId};
+use crate::common::template;
+
+// Config
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct SqliteActionConfig {
+    table: String,
+    /// Column name to its value:
+    /// - `payload` - the payload as text, or as a blob if it is not UTF-8
+    /// - `payload.<path>` - a field of a JSON payload, keeping numbers as numbers
+    /// - `now` - Unix time of the insert in seconds
+    /// - anything else is looked up in the event metadata, e.g. `topic`
+    columns: HashMap<String, String>,
+    /// Create the table with the columns if it does not exist
+    #[serde(default = "default_create_table")]
+    create_table: bool,
+}
+
+fn default_create_table() -> bool {
+    true
+}
+
+/// Row built by an action, inserted with the next batch
+#[derive(Debug, Clone)]
+pub struct SqliteRow {
+    pub sql: String,
+    pub values: Vec<SqlValue>,
+}
+
+// Action
+#[derive(Debug, Clone)]
+pub struct SqliteAction {
+    output_id: OutputId,
+    pub action_id: ActionId,
+    config: SqliteActionConfig,
+    columns: Vec<(String, String)>,
+    insert_sql: String,
+}
+
+impl Display for SqliteAction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "SqliteAction[{}::{}]", self.output_id, self.action_id)
+    }
+}
+
+impl SqliteAction {
+    pub fn new(output_id: OutputId, action_id: ActionId, config: SqliteActionConfig) -> Self {
+        let columns = config.columns.clone().into_iter().sorted().collect_vec();
+        let insert_sql = format!(
+            "INSERT INTO {} ({}) VALUES ({})",
+            quote(&config.table),
+            columns.iter().map(|(name, _)| quote(name)).join(", "),
+            columns.iter().map(|_| "?").join(", ")
+        );
+
+        Self {
+            output_id,
+            action_id,
+            config,
+            columns,
+            insert_sql,
+        }
+    }
+
+    pub fn create_table_sql(&self) -> Option<String> {
+        if !self.config.create_table {
+            return None;
+        }
+
+        Some(format!(
+            "CREATE TABLE IF NOT EXISTS {} ({})",
+            quote(&self.config.table),
+            self.columns.iter().map(|(name, _)| quote(name)).join(", ")
+        ))
+    }
+
+    pub async fn process(&self, event: &ActionableEvent) -> Option<SqliteRow> {
+        info!("Sqlite Action {} received {:?}", self.action_id, event);
+
+        let payload_json: Option<Value> = serde_json::from_slice(&event.data.payload).ok();
+        let values = self
+            .columns
+            .iter()
+            .map(|(_, source)| column_value(source, &event.data, payload_json.as_ref()))
+            .collect();
+
+        Some(SqliteRow {
+            sql: self.insert_sql.clone(),
+            values,
+        })
+    }
+}
+
+fn column_value(source: &str, data: &DataEvent, payload_json: Option<&Value>) -> SqlValue {
+    if source == "payload" {
+        return match std::str::from_utf8(&data.payload) {
+            Ok(text) => SqlValue::Text(text.to_string()),
+            Err(_) => SqlValue::Blob(data.payload.to_vec()),
+        };
+    }
+    if source == "now" {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        return SqlValue::Integer(now.as_secs() as i64);
+    }
+    if let Some(path) = source.strip_prefix("payload.") {
+        return match payload_json.and_then(|json| template::json_value(json, path)) {
+            Some(Value::Bool(value)) => SqlValue::Integer(*value as i64),
+            Some(Value::Number(value)) => match value.as_i64() {
+                Some(value) => SqlValue::Integer(value),
+                None => SqlValue::Real(value.as_f64().unwrap_or_default()),
+            },
+            Some(Value::String(value)) => SqlValue::Text(value.clone()),
+            Some(Value::Null) | None => SqlValue::Null,
+            Some(value) => SqlValue::Text(value.to_string()),
+        };
+    }
+
+    data.meta
+        .get(source)
+        .map(SqlValue::Text)
+        .unwrap_or(SqlValue::Null)
+}
+
+/// Quotes a table or column name, so any name can be used
+fn quote(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
@@ -0,0 +1,6 @@
+mod action;
+mod output;
+mod writer;
+
+pub use output::SqliteOutput;
+pub use output::SqliteOutputConfig;
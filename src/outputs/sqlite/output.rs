@@ -0,0 +1,116 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use itertools::Itertools;
+use log::{error, trace};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc::Receiver;
+
+use crate::common::data::{ActionId, ActionableEvent, ElId};
+use crate::outputs::sqlite::action::{SqliteAction, SqliteActionConfig};
+use crate::outputs::sqlite::writer::{BatchWriter, WriterThread};
+use crate::outputs::OutputTask;
+
+// Config
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct SqliteOutputConfig {
+    /// Database file, created if missing
+    path: String,
+    /// Rows inserted in one transaction
+    #[serde(default = "default_batch_size")]
+    batch_size: usize,
+    /// Longest time a row waits for the rest of its batch
+    #[serde(default = "default_flush_interval_ms")]
+    flush_interval_ms: u64,
+    #[serde(rename = "action")]
+    #[serde(default)]
+    pub actions: HashMap<ActionId, SqliteActionConfig>,
+}
+
+fn default_batch_size() -> usize {
+    100
+}
+
+fn default_flush_interval_ms() -> u64 {
+    1000
+}
+
+#[derive(Debug)]
+pub struct SqliteOutput {
+    id: ElId,
+    actions: Vec<SqliteAction>,
+    config: SqliteOutputConfig,
+}
+
+impl SqliteOutput {
+    pub fn new(id: ElId, config: SqliteOutputConfig) -> Self {
+        let actions = config
+            .actions
+            .clone()
+            .into_iter()
+            .map(|(action_id, action_config)| {
+                SqliteAction::new(id.clone(), action_id, action_config)
+            })
+            .collect_vec();
+
+        Self {
+            id,
+            actions,
+            config,
+        }
+    }
+}
+
+impl Display for SqliteOutput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "SqliteOutput[{}]", self.id)
+    }
+}
+
+#[async_trait]
+impl OutputTask for SqliteOutput {
+    async fn run(self: Box<Self>, mut chan: Receiver<ActionableEvent>) {
+        let create_sql = self
+            .actions
+            .iter()
+            .filter_map(|action| action.create_table_sql())
+            .unique()
+            .collect_vec();
+        let writer = match BatchWriter::open(
+            &self.config.path,
+            &create_sql,
+            self.config.batch_size,
+            Duration::from_millis(self.config.flush_interval_ms),
+        ) {
+            Ok(writer) => writer,
+            Err(err) => {
+                error!("{} can not open {}: {}", self, self.config.path, err);
+                return;
+            }
+        };
+
+        let rows = WriterThread::spawn(writer);
+
+        while let Some(actionable_event) = chan.recv().await {
+            trace!("{} received {:?}", &self, actionable_event);
+
+            for action in &self.actions {
+                if action.action_id != actionable_event.action {
+                    continue;
+                }
+
+                trace!("{} will process the event", action);
+                match action.process(&actionable_event).await {
+                    Some(row) => rows
+                        .send(row)
+                        .unwrap_or_else(|err| error!("{} can not queue a row: {}", action, err)),
+                    None => trace!("{} skipped the event", action),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use std::sync::mpsc::{Receiver, RecvTimeoutError, SendError, Sender};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use log::{debug, error, warn};
+use rusqlite::{params_from_iter, Connection};
+
+use crate::common::types::Result;
+use crate::outputs::sqlite::action::SqliteRow;
+
+/// Inserts rows in transactions of up to `batch_size` rows, at least every `interval`.
+/// Runs on its own thread, as rusqlite is blocking.
+pub struct BatchWriter {
+    conn: Connection,
+    batch_size: usize,
+    interval: Duration,
+    batch: Vec<SqliteRow>,
+}
+
+impl BatchWriter {
+    pub fn open(
+        path: &str,
+        create_sql: &[String],
+        batch_size: usize,
+        interval: Duration,
+    ) -> Result<Self> {
+        let conn = Connection::open(path)?;
+        // Readers like `sqlite3` do not block the inserts
+        conn.execute_batch("PRAGMA journal_mode = WAL;")?;
+        for sql in create_sql {
+            conn.execute(sql, [])?;
+        }
+
+        Ok(Self {
+            conn,
+            batch_size: batch_size.max(1),
+            interval,
+            batch: Vec::new(),
+        })
+    }
+
+    /// Consumes rows until the sender is dropped, then inserts what is left
+    pub fn run(mut self, rows: Receiver<SqliteRow>) {
+        let mut deadline: Option<Instant> = None;
+        loop {
+            let row = match deadline {
+                Some(deadline) => {
+                    rows.recv_timeout(deadline.saturating_duration_since(Instant::now()))
+                }
+                None => rows.recv().map_err(|_| RecvTimeoutError::Disconnected),
+            };
+
+            match row {
+                Ok(row) => {
+                    if self.batch.is_empty() {
+                        deadline = Some(Instant::now() + self.interval);
+                    }
+                    self.batch.push(row);
+                    if self.batch.len() < self.batch_size {
+                        continue;
+                    }
+                }
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => {
+                    self.flush();
+                    return;
+                }
+            }
+
+            self.flush();
+            deadline = None;
+        }
+    }
+
+    fn flush(&mut self) {
+        if self.batch.is_empty() {
+            return;
+        }
+
+        let rows = self.batch.len();
+        match self.insert() {
+            Ok(0) => debug!("Inserted {} rows", rows),
+            Ok(failed) => warn!("Inserted {} of {} rows", rows - failed, rows),
+            Err(err) => error!("Can not insert {} rows: {}", rows, err),
+        }
+        self.batch.clear();
+    }
+
+    /// Every row gets a savepoint, so a failing one does not take the batch with it.
+    /// Returns the number of failed rows.
+    fn insert(&mut self) -> Result<usize> {
+        let mut tx = self.conn.transaction()?;
+        let mut failed = 0;
+        for row in &self.batch {
+            let savepoint = tx.savepoint()?;
+            let inserted = savepoint
+                .prepare_cached(&row.sql)
+                .and_then(|mut statement| statement.execute(params_from_iter(row.values.iter())));
+            match inserted {
+                Ok(_) => savepoint.commit()?,
+                // Dropping the savepoint rolls the row back
+                Err(err) => {
+                    error!("Can not insert a row with `{}`: {}", row.sql, err);
+                    failed += 1;
+                }
+            }
+        }
+        tx.commit()?;
+
+        Ok(failed)
+    }
+}
+
+/// Runs a `BatchWriter` on its own thread, and waits for it to insert what is left when dropped,
+/// e.g. with the output on shutdown
+pub struct WriterThread {
+    rows: Option<Sender<SqliteRow>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl WriterThread {
+    pub fn spawn(writer: BatchWriter) -> Self {
+        let (rows, rows_receiver) = std::sync::mpsc::channel();
+        let thread = std::thread::spawn(move || writer.run(rows_receiver));
+
+        Self {
+            rows: Some(rows),
+            thread: Some(thread),
+        }
+    }
+
+    pub fn send(&self, row: SqliteRow) -> std::result::Result<(), SendError<SqliteRow>> {
+        match &self.rows {
+            Some(rows) => rows.send(row),
+            None => Err(SendError(row)),
+        }
+    }
+}
+
+impl Drop for WriterThread {
+    fn drop(&mut self) {
+        // The writer flushes and returns once the sender is gone
+        self.rows = None;
+        if let Some(thread) = self.thread.take() {
+            if thread.join().is_err() {
+                error!("SQLite writer thread panicked");
+            }
+        }
+    }
+}
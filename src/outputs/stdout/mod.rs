@@ -0,0 +1,4 @@
+mod output;
+
+pub use output::StdoutOutput;
+pub use output::StdoutOutputConfig;
@@ -0,0 +1,72 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::io::Write;
+
+use async_trait::async_trait;
+use itertools::Itertools;
+use log::{error, trace};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc::Receiver;
+
+use crate::common::data::{ActionId, ActionableEvent, ElId};
+use crate::outputs::record::{RecordAction, RecordActionConfig};
+use crate::outputs::OutputTask;
+
+// Config
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct StdoutOutputConfig {
+    #[serde(rename = "action")]
+    #[serde(default)]
+    pub actions: HashMap<ActionId, RecordActionConfig>,
+}
+
+#[derive(Debug)]
+pub struct StdoutOutput {
+    id: ElId,
+    actions: Vec<RecordAction>,
+}
+
+impl StdoutOutput {
+    pub fn new(id: ElId, config: StdoutOutputConfig) -> Self {
+        let actions = config
+            .actions
+            .into_iter()
+            .map(|(action_id, action_config)| {
+                RecordAction::new(id.clone(), action_id, action_config)
+            })
+            .collect_vec();
+
+        Self { id, actions }
+    }
+}
+
+impl Display for StdoutOutput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "StdoutOutput[{}]", self.id)
+    }
+}
+
+#[async_trait]
+impl OutputTask for StdoutOutput {
+    async fn run(self: Box<Self>, mut chan: Receiver<ActionableEvent>) {
+        while let Some(actionable_event) = chan.recv().await {
+            trace!("{} received {:?}", &self, actionable_event);
+
+            for action in &self.actions {
+                if action.action_id != actionable_event.action {
+                    continue;
+                }
+
+                if let Some(record) = action.process(&actionable_event).await {
+                    let mut stdout = std::io::stdout();
+                    stdout
+                        .write_all(&record)
+                        .and_then(|_| stdout.flush())
+                        .unwrap_or_else(|err| error!("{} can not write: {:?}", action, err));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use std::fmt::{Display, Formatter};
+
+use log::info;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::common::data::{ActionId, ActionableEvent, OutputId};
+use crate::common::template;
+use crate::common::websocket::ChannelMessage;
+use crate::outputs::payload::ActionPayloadConfig;
+
+// Config
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct WebSocketActionConfig {
+    /// `{{ placeholder }}` template, e.g. `sensors/{{ topic }}`
+    channel: String,
+    #[serde(default)]
+    payload: ActionPayloadConfig,
+}
+
+// Action
+#[derive(Debug, Clone)]
+pub struct WebSocketAction {
+    output_id: OutputId,
+    pub action_id: ActionId,
+    config: WebSocketActionConfig,
+}
+
+impl Display for WebSocketAction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "WebSocketAction[{}::{}]", self.output_id, self.action_id)
+    }
+}
+
+impl WebSocketAction {
+    pub fn new(output_id: OutputId, action_id: ActionId, config: WebSocketActionConfig) -> Self {
+        Self {
+            output_id,
+            action_id,
+            config,
+        }
+    }
+
+    pub async fn process(&self, event: &ActionableEvent) -> Option<ChannelMessage> {
+        info!("WebSocket Action {} received {:?}", self.action_id, event);
+
+        let channel = template::render(&self.config.channel, &event.data);
+        let payload = self.config.payload.build(&event.data);
+        Some(ChannelMessage::new(&channel, &payload))
+    }
+}
@@ -0,0 +1,5 @@
+mod action;
+mod output;
+
+pub use output::WebSocketOutput;
+pub use output::WebSocketOutputConfig;
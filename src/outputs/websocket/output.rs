@@ -0,0 +1,217 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Formatter};
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use futures_util::{SinkExt, StreamExt};
+use itertools::Itertools;
+use log::{debug, error, info, trace, warn};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::sync::mpsc::Receiver;
+use tokio_tungstenite::tungstenite::Message;
+
+use crate::common::data::{ActionId, ActionableEvent, ElId};
+use crate::common::secret::Secret;
+use crate::common::websocket;
+use crate::health::ComponentHealth;
+use crate::outputs::websocket::action::{WebSocketAction, WebSocketActionConfig};
+use crate::outputs::OutputTask;
+
+/// Messages a slow client can fall behind before it starts missing them
+const BROADCAST_CAPACITY: usize = 256;
+
+// Config
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct WebSocketOutputConfig {
+    listen: SocketAddr,
+    /// Require the token in `Authorization: Bearer <token>` or `?token=<token>`
+    token: Option<Secret>,
+    #[serde(rename = "action")]
+    #[serde(default)]
+    pub actions: HashMap<ActionId, WebSocketActionConfig>,
+}
+
+/// Sent by clients to change their subscriptions
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SubscriptionRequest {
+    #[serde(default)]
+    subscribe: Vec<String>,
+    #[serde(default)]
+    unsubscribe: Vec<String>,
+}
+
+/// Channel and the serialized message
+type Broadcast = (String, Arc<String>);
+
+#[derive(Debug)]
+pub struct WebSocketOutput {
+    id: ElId,
+    actions: Vec<WebSocketAction>,
+    config: WebSocketOutputConfig,
+    health: ComponentHealth,
+}
+
+impl WebSocketOutput {
+    pub fn new(id: ElId, config: WebSocketOutputConfig, health: ComponentHealth) -> Self {
+        let actions = config
+            .actions
+            .clone()
+            .into_iter()
+            .map(|(action_id, action_config)| {
+                WebSocketAction::new(id.clone(), action_id, action_config)
+            })
+            .collect_vec();
+
+        Self {
+            id,
+            actions,
+            config,
+            health,
+        }
+    }
+}
+
+impl Display for WebSocketOutput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "WebSocketOutput[{}]", self.id)
+    }
+}
+
+#[async_trait]
+impl OutputTask for WebSocketOutput {
+    async fn run(self: Box<Self>, mut chan: Receiver<ActionableEvent>) {
+        let listener = match TcpListener::bind(self.config.listen).await {
+            Ok(listener) => listener,
+            Err(err) => {
+                error!("{} can not bind to {}: {:?}", self, self.config.listen, err);
+                return;
+            }
+        };
+
+        info!("{} listening on ws://{}", self, self.config.listen);
+        self.health.set_connected(true);
+
+        let (tx, _) = broadcast::channel::<Broadcast>(BROADCAST_CAPACITY);
+        {
+            let name = self.to_string();
+            let token = self.config.token.clone();
+            let tx = tx.clone();
+            tokio::spawn(async move {
+                loop {
+                    match listener.accept().await {
+                        Ok((stream, peer)) => {
+                            let name = name.clone();
+                            let token = token.clone();
+                            let rx = tx.subscribe();
+                            tokio::spawn(async move {
+                                serve_client(&name, stream, peer, token.as_ref(), rx).await;
+                            });
+                        }
+                        Err(err) => warn!("{} can not accept a connection: {:?}", name, err),
+                    }
+                }
+            });
+        }
+
+        while let Some(actionable_event) = chan.recv().await {
+            trace!("{} received {:?}", &self, actionable_event);
+
+            for action in &self.actions {
+                if action.action_id != actionable_event.action {
+                    continue;
+                }
+
+                if let Some(message) = action.process(&actionable_event).await {
+                    match serde_json::to_string(&message) {
+                        // No receivers just means there are no clients connected
+                        Ok(text) => {
+                            let _ = tx.send((message.channel, Arc::new(text)));
+                        }
+                        Err(err) => error!("{} can not serialize the message: {:?}", action, err),
+                    }
+                }
+            }
+        }
+    }
+}
+
+async fn serve_client(
+    name: &str,
+    stream: TcpStream,
+    peer: SocketAddr,
+    token: Option<&Secret>,
+    mut rx: broadcast::Receiver<Broadcast>,
+) {
+    let (websocket, query) = match websocket::accept(stream, token).await {
+        Ok(accepted) => accepted,
+        Err(err) => {
+            debug!("{} rejected {}: {}", name, peer, err);
+            return;
+        }
+    };
+
+    // `None` means every channel, until the client picks some
+    let mut channels: Option<HashSet<String>> = None;
+    for (_, value) in query.into_iter().filter(|(key, _)| key == "channels") {
+        channels.get_or_insert_with(HashSet::new).extend(
+            value
+                .split(',')
+                .filter(|channel| !channel.is_empty())
+                .map(String::from),
+        );
+    }
+    debug!("{} accepted {} subscribed to {:?}", name, peer, channels);
+
+    let (mut sink, mut stream) = websocket.split();
+    loop {
+        tokio::select! {
+            broadcast = rx.recv() => match broadcast {
+                Ok((channel, text)) => {
+                    let wanted = match &channels {
+                        Some(channels) => channels.contains(&channel),
+                        None => true,
+                    };
+                    if wanted {
+                        if let Err(err) = sink.send(Message::Text(text.to_string())).await {
+                            debug!("{} lost {}: {}", name, peer, err);
+                            break;
+                        }
+                    }
+                }
+                Err(RecvError::Lagged(missed)) => {
+                    warn!("{} client {} is too slow, {} messages are dropped", name, peer, missed)
+                }
+                Err(RecvError::Closed) => break,
+            },
+            message = stream.next() => match message {
+                Some(Ok(Message::Text(text))) => {
+                    match serde_json::from_str::<SubscriptionRequest>(&text) {
+                        Ok(request) => {
+                            let channels = channels.get_or_insert_with(HashSet::new);
+                            channels.extend(request.subscribe);
+                            for channel in &request.unsubscribe {
+                                channels.remove(channel);
+                            }
+                            debug!("{} client {} subscribed to {:?}", name, peer, channels);
+                        }
+                        Err(err) => debug!("{} ignored a message from {}: {}", name, peer, err),
+                    }
+                }
+                Some(Ok(Message::Close(_))) | None => break,
+                Some(Ok(_)) => {}
+                Some(Err(err)) => {
+                    debug!("{} lost {}: {}", name, peer, err);
+                    break;
+                }
+            },
+        }
+    }
+
+    debug!("{} disconnected {}", name, peer);
+}